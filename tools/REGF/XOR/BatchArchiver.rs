@@ -0,0 +1,223 @@
+
+use std::fs;
+use std::io::{self, BufRead};
+use std::sync::Arc;
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::sync::Semaphore;
+use md5;
+
+use crate::{SavePageAsImage, SaveConfig, save_page_as_html};
+
+// Constants for configuration
+const DEFAULT_MAX_CONN: usize = 8;
+
+/// Which archiving operations to run for every URL in a batch
+#[derive(Debug, Clone)]
+pub struct BatchOperations {
+    pub screenshot: bool,
+    pub pdf: bool,
+    pub html: bool,
+    pub article: bool,
+    pub metadata: bool,
+}
+
+impl Default for BatchOperations {
+    fn default() -> Self {
+        BatchOperations {
+            screenshot: true,
+            pdf: false,
+            html: false,
+            article: false,
+            metadata: true,
+        }
+    }
+}
+
+/// Outcome of archiving a single URL
+#[derive(Debug)]
+pub struct BatchResult {
+    pub url: String,
+    pub outcome: Result<Vec<String>, String>,
+}
+
+/// Batch archiver that fans the chosen operations out across many URLs with
+/// bounded concurrency
+pub struct BatchArchiver {
+    save_tool: Arc<SavePageAsImage>,
+    max_conn: usize,
+    operations: BatchOperations,
+}
+
+impl BatchArchiver {
+    /// Create a new batch archiver wrapping the given save tool
+    pub fn new(save_tool: SavePageAsImage) -> Self {
+        BatchArchiver {
+            save_tool: Arc::new(save_tool),
+            max_conn: DEFAULT_MAX_CONN,
+            operations: BatchOperations::default(),
+        }
+    }
+
+    /// Set the maximum number of concurrent archiving tasks
+    pub fn set_max_conn(&mut self, max_conn: usize) {
+        self.max_conn = max_conn;
+    }
+
+    /// Choose which operations run for every URL in the batch
+    pub fn set_operations(&mut self, operations: BatchOperations) {
+        self.operations = operations;
+    }
+
+    /// Read URLs from a file, one per line, skipping blank lines
+    pub fn urls_from_file(path: &str) -> io::Result<Vec<String>> {
+        let content = fs::read_to_string(path)?;
+        Ok(Self::parse_urls(&content))
+    }
+
+    /// Read URLs from stdin, one per line, skipping blank lines
+    pub fn urls_from_stdin() -> io::Result<Vec<String>> {
+        let stdin = io::stdin();
+        let mut content = String::new();
+        for line in stdin.lock().lines() {
+            content.push_str(&line?);
+            content.push('\n');
+        }
+        Ok(Self::parse_urls(&content))
+    }
+
+    fn parse_urls(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Archive every URL concurrently, bounded by `max_conn` in-flight tasks,
+    /// and report progress with a bar. A failure on one URL does not abort
+    /// the rest of the batch.
+    pub async fn run(&self, urls: Vec<String>) -> Vec<BatchResult> {
+        let progress = ProgressBar::new(urls.len() as u64);
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .template("{bar:40.cyan/blue} {pos}/{len} {msg}")
+                .unwrap(),
+        );
+
+        let semaphore = Arc::new(Semaphore::new(self.max_conn));
+        let mut tasks = Vec::with_capacity(urls.len());
+
+        for url in urls {
+            let semaphore = Arc::clone(&semaphore);
+            let save_tool = Arc::clone(&self.save_tool);
+            let operations = self.operations.clone();
+            let progress = progress.clone();
+
+            tasks.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.unwrap();
+                progress.set_message(format!("archiving {}", url));
+
+                let outcome = tokio::task::spawn_blocking({
+                    let url = url.clone();
+                    let save_tool = Arc::clone(&save_tool);
+                    let operations = operations.clone();
+                    move || Self::archive_one(&save_tool, &url, &operations)
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()));
+
+                progress.inc(1);
+                BatchResult { url, outcome }
+            }));
+        }
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            results.push(task.await.unwrap_or_else(|e| BatchResult {
+                url: String::new(),
+                outcome: Err(e.to_string()),
+            }));
+        }
+
+        progress.finish_with_message("batch complete");
+        results
+    }
+
+    /// Run the configured operations for a single URL, collecting every
+    /// produced output path
+    fn archive_one(
+        save_tool: &SavePageAsImage,
+        url: &str,
+        operations: &BatchOperations,
+    ) -> Result<Vec<String>, String> {
+        let mut outputs = Vec::new();
+
+        if operations.screenshot {
+            outputs.push(save_tool.save(url).map_err(|e| e.to_string())?);
+        }
+        if operations.pdf {
+            outputs.push(save_tool.save_as_pdf(url).map_err(|e| e.to_string())?);
+        }
+        if operations.html {
+            // The "html" toggle means a saved HTML page, not the raw-text
+            // dump `save_text_content` produces - route it through the
+            // actual single-file HTML archiver instead
+            let output_path = format!("{}/{:x}.html", save_tool.save_path(), md5::compute(url));
+            tokio::runtime::Handle::current()
+                .block_on(save_page_as_html(url, &output_path, SaveConfig::default()))
+                .map_err(|e| e.to_string())?;
+            outputs.push(output_path);
+        }
+        if operations.article {
+            outputs.push(save_tool.save_article(url).map_err(|e| e.to_string())?);
+        }
+        if operations.metadata {
+            outputs.push(save_tool.save_metadata(url).map_err(|e| e.to_string())?);
+        }
+
+        Ok(outputs)
+    }
+
+    /// Print a summary of successes and failures after a batch run
+    pub fn print_summary(results: &[BatchResult]) {
+        let (successes, failures): (Vec<_>, Vec<_>) =
+            results.iter().partition(|r| r.outcome.is_ok());
+
+        println!(
+            "\nBatch archiving complete: {} succeeded, {} failed",
+            successes.len(),
+            failures.len()
+        );
+
+        for result in &failures {
+            if let Err(e) = &result.outcome {
+                eprintln!("  {} -> {}", result.url, e);
+            }
+        }
+    }
+}
+
+// Example usage: `cargo run --bin batch_archiver -- --file urls.txt`
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    let urls = if let Some(file_index) = args.iter().position(|a| a == "--file") {
+        let path = args.get(file_index + 1).ok_or("--file requires a path")?;
+        BatchArchiver::urls_from_file(path)?
+    } else {
+        BatchArchiver::urls_from_stdin()?
+    };
+
+    let mut save_tool = SavePageAsImage::new();
+    save_tool.set_save_path("./aluminum_saved_pages");
+
+    let mut archiver = BatchArchiver::new(save_tool);
+    archiver.set_max_conn(8);
+
+    let results = archiver.run(urls).await;
+    BatchArchiver::print_summary(&results);
+
+    Ok(())
+}
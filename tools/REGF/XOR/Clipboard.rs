@@ -0,0 +1,18 @@
+
+use arboard::Clipboard;
+
+/// Thin wrapper around the system clipboard, in the spirit of servo's
+/// `ClipboardProvider` abstraction: callers hand it a string and don't have
+/// to worry about platform-specific clipboard access or its failure modes
+/// (e.g. no display server in a headless environment)
+pub struct ClipboardProvider;
+
+impl ClipboardProvider {
+    /// Write `text` to the system clipboard
+    pub fn write(text: &str) -> Result<(), String> {
+        let mut clipboard = Clipboard::new().map_err(|e| format!("failed to access clipboard: {}", e))?;
+        clipboard
+            .set_text(text.to_string())
+            .map_err(|e| format!("failed to write to clipboard: {}", e))
+    }
+}
@@ -0,0 +1,201 @@
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use futures::stream::{self, StreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use url::Url;
+
+use crate::{fetch_url, pretty_print_html_and_links, save_to_file};
+
+// Constants for configuration
+const DEFAULT_MAX_DEPTH: usize = 2;
+const DEFAULT_MAX_CONCURRENT: usize = 8;
+const DEFAULT_SAVE_DIR: &str = "./aluminum_crawled_pages";
+
+/// Outcome of fetching a single page during a crawl
+#[derive(Debug)]
+pub struct CrawlResult {
+    pub url: String,
+    pub status_code: u16,
+    pub byte_size: usize,
+    pub depth: usize,
+}
+
+/// Same-site crawler that follows `<a href>` links discovered while
+/// pretty-printing each page, fetching a bounded number of pages
+/// concurrently via a `buffer_unordered` stream
+pub struct Crawler {
+    max_depth: usize,
+    max_concurrent: usize,
+    restrict_to_seed_host: bool,
+    mirror_to_disk: bool,
+    save_dir: String,
+}
+
+impl Crawler {
+    /// Create a crawler with sane defaults: depth 2, 8 in-flight fetches,
+    /// restricted to the seed's host, no disk mirroring
+    pub fn new() -> Self {
+        Crawler {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            restrict_to_seed_host: true,
+            mirror_to_disk: false,
+            save_dir: DEFAULT_SAVE_DIR.to_string(),
+        }
+    }
+
+    /// Set how many link hops to follow from the seed URL
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Set how many fetches may be in flight at once
+    pub fn set_max_concurrent(&mut self, max_concurrent: usize) {
+        self.max_concurrent = max_concurrent;
+    }
+
+    /// Allow or disallow following links to hosts other than the seed's
+    pub fn set_restrict_to_seed_host(&mut self, restrict: bool) {
+        self.restrict_to_seed_host = restrict;
+    }
+
+    /// Mirror every fetched page to `save_dir` as it's fetched
+    pub fn set_mirror_to_disk(&mut self, mirror: bool, save_dir: &str) {
+        self.mirror_to_disk = mirror;
+        self.save_dir = save_dir.to_string();
+    }
+
+    /// Crawl starting from `seed_url`, returning one `CrawlResult` per page
+    /// fetched. Links are resolved against the page that contained them,
+    /// deduplicated against a visited set, and (optionally) restricted to
+    /// the seed's host before being queued for the next depth.
+    pub async fn crawl(&self, seed_url: &str) -> Result<Vec<CrawlResult>, Box<dyn std::error::Error>> {
+        let seed = Url::parse(seed_url)?;
+        let seed_host = seed.host_str().unwrap_or("").to_string();
+
+        let visited = Arc::new(Mutex::new(HashSet::new()));
+        visited.lock().unwrap().insert(seed_url.to_string());
+
+        let multi = MultiProgress::new();
+        let overall = multi.add(ProgressBar::new_spinner());
+        overall.set_style(ProgressStyle::default_spinner().template("{spinner:.green} {msg}").unwrap());
+        overall.set_message("crawling...");
+
+        let mut frontier: Vec<(String, usize)> = vec![(seed_url.to_string(), 0)];
+        let mut results = Vec::new();
+
+        while !frontier.is_empty() {
+            let batch = std::mem::take(&mut frontier);
+
+            let fetched: Vec<(String, usize, Result<(u16, String), String>)> = stream::iter(batch)
+                .map(|(url, depth)| {
+                    let multi = multi.clone();
+                    async move {
+                        let bar = multi.add(ProgressBar::new_spinner());
+                        bar.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}").unwrap());
+                        bar.set_message(format!("fetching {}", url));
+
+                        let outcome = fetch_url(&url).await.map_err(|e| e.to_string());
+
+                        bar.finish_and_clear();
+                        (url, depth, outcome)
+                    }
+                })
+                .buffer_unordered(self.max_concurrent)
+                .collect()
+                .await;
+
+            for (url, depth, outcome) in fetched {
+                overall.inc(1);
+
+                let (status_code, body) = match outcome {
+                    Ok(fetched) => fetched,
+                    Err(e) => {
+                        eprintln!("  {} -> {}", url, e);
+                        continue;
+                    }
+                };
+
+                results.push(CrawlResult {
+                    url: url.clone(),
+                    status_code,
+                    byte_size: body.len(),
+                    depth,
+                });
+
+                if self.mirror_to_disk {
+                    let path = Self::path_for_url(&self.save_dir, &url);
+                    if let Err(e) = save_to_file(&body, &path) {
+                        eprintln!("  {} -> failed to mirror to {}: {}", url, path, e);
+                    }
+                }
+
+                if depth >= self.max_depth {
+                    continue;
+                }
+
+                let (_, links) = pretty_print_html_and_links(&body);
+                for link in links {
+                    let resolved = match Url::parse(&url).and_then(|base| base.join(&link)) {
+                        Ok(resolved) => resolved,
+                        Err(_) => continue,
+                    };
+
+                    if self.restrict_to_seed_host && resolved.host_str() != Some(seed_host.as_str()) {
+                        continue;
+                    }
+
+                    let resolved_str = resolved.to_string();
+                    let mut visited_guard = visited.lock().unwrap();
+                    if visited_guard.insert(resolved_str.clone()) {
+                        drop(visited_guard);
+                        frontier.push((resolved_str, depth + 1));
+                    }
+                }
+            }
+        }
+
+        overall.finish_with_message("crawl complete");
+        Ok(results)
+    }
+
+    /// Derive a filesystem path for mirroring a fetched page, flattening the
+    /// URL into a single safe filename under `save_dir`
+    fn path_for_url(save_dir: &str, url: &str) -> String {
+        let sanitized: String = url
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}/{}.html", save_dir, sanitized)
+    }
+
+    /// Print a summary of every page fetched: URL, status code, byte size,
+    /// and the depth it was discovered at
+    pub fn print_summary(results: &[CrawlResult]) {
+        println!("\nCrawl complete: {} page(s) fetched", results.len());
+        for result in results {
+            println!(
+                "  [depth {}] {} -> {} ({} bytes)",
+                result.depth, result.url, result.status_code, result.byte_size
+            );
+        }
+    }
+}
+
+// Example usage: `cargo run --bin crawler -- https://example.com`
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let seed_url = args.get(1).ok_or("usage: crawler <seed-url>")?;
+
+    std::fs::create_dir_all(DEFAULT_SAVE_DIR).ok();
+
+    let mut crawler = Crawler::new();
+    crawler.set_mirror_to_disk(true, DEFAULT_SAVE_DIR);
+
+    let results = crawler.crawl(seed_url).await?;
+    Crawler::print_summary(&results);
+
+    Ok(())
+}
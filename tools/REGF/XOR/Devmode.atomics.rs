@@ -7,6 +7,13 @@ use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
+use crate::{Permissions, PermissionGrant, PermissionError};
+
+// HAR format version emitted by `export_har`
+const HAR_VERSION: &str = "1.2";
+const HAR_CREATOR_NAME: &str = "Aluminum";
+const HAR_CREATOR_VERSION: &str = "1.0";
+
 // Define the DevMode struct to hold all developer tools and settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DevMode {
@@ -21,6 +28,8 @@ pub struct DevMode {
     user_agent: String,
     viewport_size: (u32, u32),
     emulation_settings: EmulationSettings,
+    #[serde(skip)]
+    permissions: Permissions,
 }
 
 // NetworkRequest struct to store information about network requests
@@ -32,6 +41,7 @@ struct NetworkRequest {
     body: Option<String>,
     response: Option<NetworkResponse>,
     timestamp: DateTime<Utc>,
+    permission_grant: Option<PermissionGrant>,
 }
 
 // NetworkResponse struct to store information about network responses
@@ -99,6 +109,7 @@ impl DevMode {
             user_agent: String::from("Aluminum/1.0"),
             viewport_size: (1920, 1080),
             emulation_settings: EmulationSettings::default(),
+            permissions: Permissions::new(),
         }
     }
 
@@ -121,6 +132,54 @@ impl DevMode {
         }
     }
 
+    // Check whether `url` is permitted by the host-permission sandbox
+    pub fn check_permission(&self, url: &str) -> Result<PermissionGrant, PermissionError> {
+        self.permissions.check(url)
+    }
+
+    // Add a host pattern to the permission sandbox's allowlist
+    pub fn allow_host(&mut self, pattern: &str) {
+        self.permissions.allow_host(pattern);
+    }
+
+    // Add a host pattern to the permission sandbox's denylist
+    pub fn deny_host(&mut self, pattern: &str) {
+        self.permissions.deny_host(pattern);
+    }
+
+    // Enable or disable interactive prompting for unlisted hosts
+    pub fn set_permissions_interactive(&mut self, interactive: bool) {
+        self.permissions.set_interactive(interactive);
+    }
+
+    // Record a completed fetch, tagging it with how the host was authorized
+    pub fn record_fetch(
+        &mut self,
+        url: &str,
+        method: &str,
+        status: u16,
+        grant: PermissionGrant,
+        request_headers: HashMap<String, String>,
+        response_headers: HashMap<String, String>,
+        response_body: Option<String>,
+    ) {
+        if self.enabled {
+            self.network_requests.push(NetworkRequest {
+                url: url.to_string(),
+                method: method.to_string(),
+                headers: request_headers,
+                body: None,
+                response: Some(NetworkResponse {
+                    status,
+                    headers: response_headers,
+                    body: response_body,
+                }),
+                timestamp: Utc::now(),
+                permission_grant: Some(grant),
+            });
+        }
+    }
+
     // Update performance metrics
     pub fn update_performance_metrics(&mut self, metrics: PerformanceMetrics) {
         if self.enabled {
@@ -274,6 +333,41 @@ pub fn record_network_request(request: NetworkRequest) {
     devmode.record_network_request(request);
 }
 
+// Check a URL against the host-permission sandbox before fetching it
+pub fn check_permission(url: &str) -> Result<PermissionGrant, PermissionError> {
+    let devmode = DEVMODE.lock().unwrap();
+    devmode.check_permission(url)
+}
+
+pub fn allow_host(pattern: &str) {
+    let mut devmode = DEVMODE.lock().unwrap();
+    devmode.allow_host(pattern);
+}
+
+pub fn deny_host(pattern: &str) {
+    let mut devmode = DEVMODE.lock().unwrap();
+    devmode.deny_host(pattern);
+}
+
+pub fn set_permissions_interactive(interactive: bool) {
+    let mut devmode = DEVMODE.lock().unwrap();
+    devmode.set_permissions_interactive(interactive);
+}
+
+// Record a fetch in the network log, tagged with its resolved permission grant
+pub fn record_fetch(
+    url: &str,
+    method: &str,
+    status: u16,
+    grant: PermissionGrant,
+    request_headers: HashMap<String, String>,
+    response_headers: HashMap<String, String>,
+    response_body: Option<String>,
+) {
+    let mut devmode = DEVMODE.lock().unwrap();
+    devmode.record_fetch(url, method, status, grant, request_headers, response_headers, response_body);
+}
+
 pub fn update_performance_metrics(metrics: PerformanceMetrics) {
     let mut devmode = DEVMODE.lock().unwrap();
     devmode.update_performance_metrics(metrics);
@@ -354,12 +448,18 @@ pub fn get_network_requests_summary() -> String {
     let devmode = DEVMODE.lock().unwrap();
     let mut summary = String::new();
     for (index, request) in devmode.network_requests.iter().enumerate() {
+        let grant = match request.permission_grant {
+            Some(PermissionGrant::Allowlisted) => " [allowlisted]",
+            Some(PermissionGrant::UserApproved) => " [user-approved]",
+            None => "",
+        };
         summary.push_str(&format!(
-            "Request {}: {} {} (Status: {})\n",
+            "Request {}: {} {} (Status: {}){}\n",
             index + 1,
             request.method,
             request.url,
-            request.response.as_ref().map_or(0, |r| r.status)
+            request.response.as_ref().map_or(0, |r| r.status),
+            grant
         ));
     }
     summary
@@ -427,4 +527,201 @@ pub fn generate_devmode_report() -> String {
     report.push_str(&format!("Viewport Size: {}x{}\n\n", devmode.viewport_size.0, devmode.viewport_size.1));
 
     report.push_str("Performance Metrics:\n");
-    report.
+    report.push_str(&get_performance_summary());
+    report.push_str("\n\nNetwork Requests:\n");
+    report.push_str(&get_network_requests_summary());
+
+    report
+}
+
+// HAR (HTTP Archive) 1.2 document produced by `export_har`, for opening
+// Aluminum's captured traffic in browser devtools or any HAR analyzer
+#[derive(Serialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Serialize)]
+struct HarLog {
+    version: String,
+    creator: HarCreator,
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Serialize)]
+struct HarCreator {
+    name: String,
+    version: String,
+}
+
+#[derive(Serialize)]
+struct HarNameValue {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct HarPostData {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(rename = "httpVersion")]
+    http_version: String,
+    headers: Vec<HarNameValue>,
+    #[serde(rename = "queryString")]
+    query_string: Vec<HarNameValue>,
+    #[serde(rename = "postData", skip_serializing_if = "Option::is_none")]
+    post_data: Option<HarPostData>,
+    #[serde(rename = "headersSize")]
+    headers_size: i64,
+    #[serde(rename = "bodySize")]
+    body_size: i64,
+}
+
+#[derive(Serialize)]
+struct HarContent {
+    size: i64,
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HarResponse {
+    status: u16,
+    #[serde(rename = "statusText")]
+    status_text: String,
+    #[serde(rename = "httpVersion")]
+    http_version: String,
+    headers: Vec<HarNameValue>,
+    content: HarContent,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    #[serde(rename = "headersSize")]
+    headers_size: i64,
+    #[serde(rename = "bodySize")]
+    body_size: i64,
+}
+
+#[derive(Serialize)]
+struct HarTimings {
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+#[derive(Serialize)]
+struct HarEntry {
+    #[serde(rename = "startedDateTime")]
+    started_date_time: String,
+    request: HarRequest,
+    response: HarResponse,
+    timings: HarTimings,
+}
+
+// Flatten a headers map into HAR's ordered name/value pairs
+fn flatten_headers(headers: &HashMap<String, String>) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .map(|(name, value)| HarNameValue { name: name.clone(), value: value.clone() })
+        .collect()
+}
+
+// Look up a header case-insensitively (HAR headers are flattened from a
+// plain HashMap, so casing isn't guaranteed to match HTTP conventions)
+fn header_lookup<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+}
+
+// Approximate per-entry timings from DevMode's page-level performance
+// metrics, since NetworkRequest doesn't track per-request timing
+fn timings_from_metrics(metrics: &PerformanceMetrics) -> HarTimings {
+    let wait = metrics.time_to_interactive * 1000.0;
+    let receive = (metrics.page_load_time - metrics.time_to_interactive).max(0.0) * 1000.0;
+    HarTimings { send: 0.0, wait, receive }
+}
+
+/// Export DevMode's recorded network requests as a HAR 1.2 document,
+/// consumable by browser devtools and HAR analyzers
+pub fn export_har() -> String {
+    let devmode = DEVMODE.lock().unwrap();
+
+    let entries = devmode
+        .network_requests
+        .iter()
+        .map(|request| {
+            let request_headers = flatten_headers(&request.headers);
+            let post_data = request.body.as_ref().map(|body| HarPostData {
+                mime_type: header_lookup(&request.headers, "content-type")
+                    .unwrap_or("application/octet-stream")
+                    .to_string(),
+                text: body.clone(),
+            });
+
+            let response = match &request.response {
+                Some(response) => HarResponse {
+                    status: response.status,
+                    status_text: String::new(),
+                    http_version: "HTTP/1.1".to_string(),
+                    headers: flatten_headers(&response.headers),
+                    content: HarContent {
+                        size: response.body.as_ref().map_or(0, |body| body.len() as i64),
+                        mime_type: header_lookup(&response.headers, "content-type")
+                            .unwrap_or("text/html")
+                            .to_string(),
+                        text: response.body.clone(),
+                    },
+                    redirect_url: String::new(),
+                    headers_size: -1,
+                    body_size: response.body.as_ref().map_or(0, |body| body.len() as i64),
+                },
+                None => HarResponse {
+                    status: 0,
+                    status_text: String::new(),
+                    http_version: "HTTP/1.1".to_string(),
+                    headers: Vec::new(),
+                    content: HarContent { size: 0, mime_type: String::new(), text: None },
+                    redirect_url: String::new(),
+                    headers_size: -1,
+                    body_size: 0,
+                },
+            };
+
+            HarEntry {
+                started_date_time: request.timestamp.to_rfc3339(),
+                request: HarRequest {
+                    method: request.method.clone(),
+                    url: request.url.clone(),
+                    http_version: "HTTP/1.1".to_string(),
+                    headers: request_headers,
+                    query_string: Vec::new(),
+                    post_data,
+                    headers_size: -1,
+                    body_size: request.body.as_ref().map_or(0, |body| body.len() as i64),
+                },
+                response,
+                timings: timings_from_metrics(&devmode.performance_metrics),
+            }
+        })
+        .collect();
+
+    let har = Har {
+        log: HarLog {
+            version: HAR_VERSION.to_string(),
+            creator: HarCreator {
+                name: HAR_CREATOR_NAME.to_string(),
+                version: HAR_CREATOR_VERSION.to_string(),
+            },
+            entries,
+        },
+    };
+
+    serde_json::to_string_pretty(&har).unwrap_or_else(|_| String::from("Failed to export HAR data"))
+}
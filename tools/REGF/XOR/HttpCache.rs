@@ -0,0 +1,70 @@
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+
+/// A previously fetched resource, modeled on Deno's `FileCache`: enough to
+/// serve conditional GETs and check freshness without a network round trip
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub body: String,
+    pub status: u16,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+    pub max_age: Option<u64>,
+}
+
+impl CachedResponse {
+    /// Whether this entry is still fresh under its `Cache-Control: max-age`.
+    /// An entry with no freshness claim is always treated as stale, so it's
+    /// revalidated rather than assumed good forever.
+    pub fn is_fresh(&self) -> bool {
+        match self.max_age {
+            Some(max_age) => {
+                let age = (Utc::now() - self.fetched_at).num_seconds().max(0) as u64;
+                age < max_age
+            }
+            None => false,
+        }
+    }
+}
+
+// Process-wide cache of fetched pages, keyed by URL
+lazy_static! {
+    static ref HTTP_CACHE: Arc<Mutex<HashMap<String, CachedResponse>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// Look up a cached entry for `url`, regardless of freshness
+pub fn get_cached(url: &str) -> Option<CachedResponse> {
+    let cache = HTTP_CACHE.lock().unwrap();
+    cache.get(url).cloned()
+}
+
+/// Store or replace the cached entry for `url`
+pub fn store_cached(url: &str, response: CachedResponse) {
+    let mut cache = HTTP_CACHE.lock().unwrap();
+    cache.insert(url.to_string(), response);
+}
+
+/// Drop every cached entry
+pub fn clear_cache() {
+    let mut cache = HTTP_CACHE.lock().unwrap();
+    cache.clear();
+}
+
+/// Summarize cache occupancy for diagnostics
+pub fn cache_stats() -> String {
+    let cache = HTTP_CACHE.lock().unwrap();
+    let fresh = cache.values().filter(|r| r.is_fresh()).count();
+    format!("{} cached response(s), {} fresh, {} stale", cache.len(), fresh, cache.len() - fresh)
+}
+
+/// Parse the `max-age` directive out of a `Cache-Control` header value
+pub fn parse_max_age(cache_control: &str) -> Option<u64> {
+    cache_control
+        .split(',')
+        .map(|directive| directive.trim())
+        .find_map(|directive| directive.strip_prefix("max-age="))
+        .and_then(|value| value.parse::<u64>().ok())
+}
@@ -12,9 +12,63 @@ use wry::{
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-// Define the structure for element information
+use crate::StringInterner;
+use crate::NavigationHistory;
+use crate::ClipboardProvider;
+
+// Sweep the string interner every this-many IPC calls, rather than on every
+// single one, to keep the hot path cheap
+const INTERNER_SWEEP_INTERVAL: usize = 50;
+
+lazy_static! {
+    // Process-wide table of interned attribute/style names and values
+    static ref INTERNER: StringInterner = StringInterner::new();
+}
+
+// One link of the ancestor chain walked when generating a CSS selector:
+// enough to reproduce the same id/tag.class/:nth-child(n) choice `generate_
+// selector` makes for the element itself, one level up
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AncestorInfo {
+    tag_name: String,
+    id: String,
+    classes: Vec<String>,
+    nth_child: usize,
+}
+
+// Define the structure for element information
+#[derive(Debug, Clone, Serialize)]
 struct ElementInfo {
+    tag_name: String,
+    id: String,
+    classes: Vec<String>,
+    attributes: HashMap<Arc<str>, Arc<str>>,
+    computed_styles: HashMap<Arc<str>, Arc<str>>,
+    inner_text: String,
+    children_count: usize,
+    // Accessibility-tree view of the same element: computed role, the
+    // accessible name resolved through the standard precedence, and the ids
+    // of any elements it relocates into its subtree via `aria-owns`
+    role: String,
+    accessible_name: String,
+    owned_children: Vec<String>,
+    // 1-based position among this element's siblings, used as the
+    // `:nth-child(n)` fallback when generating a CSS selector for an
+    // element with neither an id nor a class
+    nth_child: usize,
+    // Ancestor chain from the immediate parent up to either the nearest
+    // ancestor with an id or the document root, walked when generating a
+    // CSS selector for this element
+    ancestors: Vec<AncestorInfo>,
+}
+
+// Wire format for an inspected element as sent from JS: plain strings,
+// interned into `ElementInfo` right after parsing so duplicated property
+// names/values across elements collapse to shared `Arc<str>` handles
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawElementInfo {
     tag_name: String,
     id: String,
     classes: Vec<String>,
@@ -22,13 +76,167 @@ struct ElementInfo {
     computed_styles: HashMap<String, String>,
     inner_text: String,
     children_count: usize,
+    role: String,
+    accessible_name: String,
+    owned_children: Vec<String>,
+    nth_child: usize,
+    ancestors: Vec<AncestorInfo>,
+}
+
+impl ElementInfo {
+    fn from_raw(raw: RawElementInfo) -> Self {
+        ElementInfo {
+            tag_name: raw.tag_name,
+            id: raw.id,
+            classes: raw.classes,
+            attributes: intern_map(&raw.attributes),
+            computed_styles: intern_map(&raw.computed_styles),
+            inner_text: raw.inner_text,
+            children_count: raw.children_count,
+            role: raw.role,
+            accessible_name: raw.accessible_name,
+            owned_children: raw.owned_children,
+            nth_child: raw.nth_child,
+            ancestors: raw.ancestors,
+        }
+    }
+}
+
+// Render the selector segment for a single node: its id if it has one,
+// else its tag qualified by classes, else its tag qualified by
+// `:nth-child(n)` among its siblings
+fn selector_segment(tag_name: &str, id: &str, classes: &[String], nth_child: usize) -> String {
+    if !id.is_empty() {
+        return format!("#{}", id);
+    }
+    if !classes.is_empty() {
+        return format!("{}.{}", tag_name, classes.join("."));
+    }
+    format!("{}:nth-child({})", tag_name, nth_child)
+}
+
+// Generate a CSS selector for `element` by walking from its nearest
+// ancestor with an id (or the document root, whichever comes first) down to
+// the element itself, joining each segment with `>`. A `tag.class` segment
+// is not disambiguated among siblings sharing that tag and those classes,
+// so the selector can match more than one element.
+fn generate_selector(element: &ElementInfo) -> String {
+    let mut segments: Vec<String> = element
+        .ancestors
+        .iter()
+        .map(|ancestor| selector_segment(&ancestor.tag_name, &ancestor.id, &ancestor.classes, ancestor.nth_child))
+        .collect();
+    segments.push(selector_segment(&element.tag_name, &element.id, &element.classes, element.nth_child));
+    segments.join(" > ")
+}
+
+// Render `element`'s cached computed styles as a formatted CSS rule block
+fn generate_styles_block(element: &ElementInfo) -> String {
+    let mut properties: Vec<(&Arc<str>, &Arc<str>)> = element.computed_styles.iter().collect();
+    properties.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut block = format!("{} {{\n", generate_selector(element));
+    for (name, value) in properties {
+        block.push_str(&format!("  {}: {};\n", name, value));
+    }
+    block.push('}');
+    block
+}
+
+// Intern every key and value of a plain string map through `INTERNER`
+fn intern_map(map: &HashMap<String, String>) -> HashMap<Arc<str>, Arc<str>> {
+    map.iter().map(|(k, v)| (INTERNER.intern(k), INTERNER.intern(v))).collect()
+}
+
+// A node in the computed accessibility tree, distinct from `ElementInfo`
+// since `aria-owns` can relocate a node under an owner that isn't its DOM
+// parent
+#[derive(Debug, Clone, Serialize)]
+struct AccessibilityNode {
+    id: String,
+    role: String,
+    accessible_name: String,
+    children: Vec<AccessibilityNode>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AccessibilityTree {
+    roots: Vec<AccessibilityNode>,
+}
+
+impl AccessibilityTree {
+    // Build the accessibility tree from every element inspected so far,
+    // relocating `aria-owns` targets under their owner the way Gecko's
+    // DoARIAOwnsRelocation does: the owned subtree is built in full before
+    // it's attached to its owner, and it's never also left under its DOM
+    // parent, so ordering stays stable and nothing is duplicated
+    fn build(elements: &[ElementInfo]) -> Self {
+        let by_id: HashMap<&str, &ElementInfo> = elements
+            .iter()
+            .filter(|e| !e.id.is_empty())
+            .map(|e| (e.id.as_str(), e))
+            .collect();
+
+        let relocated: std::collections::HashSet<&str> = elements
+            .iter()
+            .flat_map(|e| e.owned_children.iter())
+            .map(|id| id.as_str())
+            .filter(|id| by_id.contains_key(id))
+            .collect();
+
+        // `placed` tracks every id already attached somewhere in the tree so
+        // an `aria-owns` cycle (or the same id listed by two different
+        // owners) can't recurse forever or duplicate a node: an id is only
+        // ever built once, on whichever branch reaches it first
+        fn build_node<'a>(
+            element: &'a ElementInfo,
+            by_id: &HashMap<&str, &'a ElementInfo>,
+            placed: &mut std::collections::HashSet<&'a str>,
+        ) -> AccessibilityNode {
+            let children = element
+                .owned_children
+                .iter()
+                .filter_map(|id| by_id.get(id.as_str()).map(|owned| (id.as_str(), *owned)))
+                .filter(|(id, _)| !placed.contains(id))
+                .map(|(id, owned)| {
+                    placed.insert(id);
+                    build_node(owned, by_id, placed)
+                })
+                .collect();
+            AccessibilityNode {
+                id: element.id.clone(),
+                role: element.role.clone(),
+                accessible_name: element.accessible_name.clone(),
+                children,
+            }
+        }
+
+        let mut placed: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let roots = elements
+            .iter()
+            .filter(|e| e.id.is_empty() || !relocated.contains(e.id.as_str()))
+            .filter_map(|e| {
+                if !e.id.is_empty() && !placed.insert(e.id.as_str()) {
+                    return None;
+                }
+                Some(build_node(e, &by_id, &mut placed))
+            })
+            .collect();
+
+        AccessibilityTree { roots }
+    }
 }
 
 // Define the Inspector struct to manage the inspection state
 struct Inspector {
     selected_element: Option<ElementInfo>,
     history: Vec<ElementInfo>,
-    styles_cache: HashMap<String, HashMap<String, String>>,
+    styles_cache: HashMap<String, HashMap<Arc<str>, Arc<str>>>,
+    ipc_call_count: usize,
+    // Whether the page is currently in inspect mode, i.e. whether the
+    // right-click context menu should offer inspection actions at all.
+    // Off by default so a freshly-loaded page behaves like a normal browser.
+    inspect_mode: bool,
 }
 
 impl Inspector {
@@ -37,9 +245,16 @@ impl Inspector {
             selected_element: None,
             history: Vec::new(),
             styles_cache: HashMap::new(),
+            ipc_call_count: 0,
+            inspect_mode: false,
         }
     }
 
+    // Toggle inspect mode on or off
+    fn set_inspect_mode(&mut self, enabled: bool) {
+        self.inspect_mode = enabled;
+    }
+
     // Select an element and update the history
     fn select_element(&mut self, element: ElementInfo) {
         if let Some(current) = &self.selected_element {
@@ -55,16 +270,37 @@ impl Inspector {
     }
 
     // Cache computed styles for an element
-    fn cache_styles(&mut self, element_id: String, styles: HashMap<String, String>) {
+    fn cache_styles(&mut self, element_id: String, styles: HashMap<Arc<str>, Arc<str>>) {
         self.styles_cache.insert(element_id, styles);
     }
 
     // Retrieve cached styles for an element
-    fn get_cached_styles(&self, element_id: &str) -> Option<&HashMap<String, String>> {
+    fn get_cached_styles(&self, element_id: &str) -> Option<&HashMap<Arc<str>, Arc<str>>> {
         self.styles_cache.get(element_id)
     }
+
+    // Every element inspected so far, oldest first, including the
+    // currently-selected one; the input the accessibility tree is built from
+    fn inspected_elements(&self) -> Vec<ElementInfo> {
+        let mut elements = self.history.clone();
+        if let Some(selected) = &self.selected_element {
+            elements.push(selected.clone());
+        }
+        elements
+    }
+
+    // Count an IPC dispatch, periodically sweeping the interner so strings
+    // whose last `Arc` reference has been dropped don't linger forever
+    fn note_ipc_call(&mut self) {
+        self.ipc_call_count += 1;
+        if self.ipc_call_count % INTERNER_SWEEP_INTERVAL == 0 {
+            INTERNER.sweep();
+        }
+    }
 }
 
+const SEED_URL: &str = "https://www.Aluminum.com/inspectElement.html";
+
 // Main function to run the Aluminum browser with inspect element functionality
 fn main() -> wry::Result<()> {
     // Create an event loop and window
@@ -76,13 +312,56 @@ fn main() -> wry::Result<()> {
     // Create a shared inspector instance
     let inspector = Arc::new(Mutex::new(Inspector::new()));
 
+    // Page-navigation history, kept separate from the inspector's
+    // element-selection history so the two work independently
+    let nav_history = Arc::new(Mutex::new(NavigationHistory::new()));
+    nav_history.lock().unwrap().visit(SEED_URL);
+
     // Create the WebView
     let webview = WebViewBuilder::new(window)?
-        .with_url("https://www.Aluminum.com/inspectElement.html")?
-        .with_initialization_script(include_str!("inspect_element.js"))
-        .with_ipc_handler(move |_, message| {
-            let mut inspector = inspector.lock().unwrap();
-            handle_ipc_message(&mut inspector, message);
+        .with_url(SEED_URL)?
+        .with_initialization_script(INSPECT_ELEMENT_JS)
+        .with_ipc_handler(move |webview, message| {
+            let data: serde_json::Value = match serde_json::from_str(&message) {
+                Ok(data) => data,
+                Err(_) => return,
+            };
+            let callback_id = data["callbackId"].as_i64().unwrap_or(0);
+
+            let result = match data["action"].as_str() {
+                Some("navigate") => {
+                    if let Some(url) = data["url"].as_str() {
+                        nav_history.lock().unwrap().visit(url);
+                    }
+                    Ok(json!({ "recorded": true }))
+                }
+                Some("go_back_page") => match nav_history.lock().unwrap().go_back() {
+                    Some(url) => {
+                        let _ = webview.load_url(&url);
+                        Ok(json!({ "url": url }))
+                    }
+                    None => Err("no previous page in navigation history".to_string()),
+                },
+                Some("go_forward_page") => match nav_history.lock().unwrap().go_forward() {
+                    Some(url) => {
+                        let _ = webview.load_url(&url);
+                        Ok(json!({ "url": url }))
+                    }
+                    None => Err("no next page in navigation history".to_string()),
+                },
+                _ => {
+                    let mut inspector = inspector.lock().unwrap();
+                    handle_ipc_message(&mut inspector, &data)
+                }
+            };
+
+            let payload = match result {
+                Ok(value) => json!({ "ok": true, "result": value }),
+                Err(error) => json!({ "ok": false, "error": error }),
+            };
+
+            let script = format!("window.__resolveIpc({}, {})", callback_id, payload);
+            let _ = webview.evaluate_script(&script);
         })
         .build()?;
 
@@ -101,43 +380,58 @@ fn main() -> wry::Result<()> {
     });
 }
 
-// Handle IPC messages from the JavaScript side
-fn handle_ipc_message(inspector: &mut Inspector, message: String) {
-    let data: serde_json::Value = serde_json::from_str(&message).unwrap();
-    
+// Handle IPC messages from the JavaScript side, returning the value (or
+// error) to resolve (or reject) the page's matching `callbackId` Promise
+fn handle_ipc_message(inspector: &mut Inspector, data: &serde_json::Value) -> Result<serde_json::Value, String> {
+    inspector.note_ipc_call();
+
     match data["action"].as_str() {
         Some("select_element") => {
-            if let Ok(element_info) = serde_json::from_value(data["element"].clone()) {
-                inspector.select_element(element_info);
-                println!("Selected element: {:?}", inspector.selected_element);
-            }
+            let raw = serde_json::from_value::<RawElementInfo>(data["element"].clone())
+                .map_err(|e| format!("invalid element data: {}", e))?;
+            inspector.select_element(ElementInfo::from_raw(raw));
+            Ok(json!({ "selected": true }))
         }
         Some("get_computed_styles") => {
-            if let Some(element_id) = data["elementId"].as_str() {
-                if let Some(styles) = inspector.get_cached_styles(element_id) {
-                    println!("Retrieved cached styles for element {}: {:?}", element_id, styles);
-                } else {
-                    println!("Styles not found in cache for element {}", element_id);
-                }
+            let element_id = data["elementId"].as_str().ok_or("missing elementId")?;
+            match inspector.get_cached_styles(element_id) {
+                Some(styles) => Ok(serde_json::to_value(styles).unwrap()),
+                None => Err(format!("styles not found in cache for element {}", element_id)),
             }
         }
         Some("cache_computed_styles") => {
-            if let (Some(element_id), Ok(styles)) = (
-                data["elementId"].as_str(),
-                serde_json::from_value::<HashMap<String, String>>(data["styles"].clone()),
-            ) {
-                inspector.cache_styles(element_id.to_string(), styles);
-                println!("Cached styles for element {}", element_id);
-            }
+            let element_id = data["elementId"].as_str().ok_or("missing elementId")?;
+            let styles = serde_json::from_value::<HashMap<String, String>>(data["styles"].clone())
+                .map_err(|e| format!("invalid styles: {}", e))?;
+            inspector.cache_styles(element_id.to_string(), intern_map(&styles));
+            Ok(json!({ "cached": true }))
         }
-        Some("go_back") => {
-            if let Some(previous_element) = inspector.go_back() {
-                println!("Navigated back to element: {:?}", previous_element);
-            } else {
-                println!("No previous element in history");
-            }
+        Some("go_back") => match inspector.go_back() {
+            Some(previous_element) => Ok(serde_json::to_value(previous_element).unwrap()),
+            None => Err("no previous element in history".to_string()),
+        },
+        Some("get_accessibility_tree") => {
+            let tree = AccessibilityTree::build(&inspector.inspected_elements());
+            Ok(serde_json::to_value(tree).unwrap())
+        }
+        Some("set_inspect_mode") => {
+            let enabled = data["enabled"].as_bool().unwrap_or(false);
+            inspector.set_inspect_mode(enabled);
+            Ok(json!({ "inspectMode": enabled }))
+        }
+        Some("copy_selector") => {
+            let element = inspector.selected_element.as_ref().ok_or("no element selected")?;
+            let selector = generate_selector(element);
+            ClipboardProvider::write(&selector)?;
+            Ok(json!({ "copied": selector }))
         }
-        _ => println!("Unknown action received"),
+        Some("copy_styles") => {
+            let element = inspector.selected_element.as_ref().ok_or("no element selected")?;
+            let block = generate_styles_block(element);
+            ClipboardProvider::write(&block)?;
+            Ok(json!({ "copied": block }))
+        }
+        _ => Err("unknown action received".to_string()),
     }
 }
 
@@ -146,6 +440,146 @@ const INSPECT_ELEMENT_JS: &str = r#"
 (function() {
     let selectedElement = null;
 
+    // Pending callbacks for in-flight `invoke()` calls, keyed by callbackId.
+    // Rust resolves/rejects these via `window.__resolveIpc` once it's done.
+    window.__alCallbacks = {};
+    let nextCallbackId = 1;
+
+    window.__resolveIpc = function(callbackId, payload) {
+        const pending = window.__alCallbacks[callbackId];
+        if (!pending) {
+            return;
+        }
+        delete window.__alCallbacks[callbackId];
+        if (payload.ok) {
+            pending.resolve(payload.result);
+        } else {
+            pending.reject(new Error(payload.error));
+        }
+    };
+
+    // Send an action to Rust and return a Promise that resolves/rejects
+    // when `window.__resolveIpc` is called with this request's callbackId
+    function invoke(action, data) {
+        return new Promise((resolve, reject) => {
+            const callbackId = nextCallbackId++;
+            window.__alCallbacks[callbackId] = { resolve, reject };
+            window.ipc.postMessage(JSON.stringify(Object.assign({ action, callbackId }, data)));
+        });
+    }
+
+    // Tag -> implicit ARIA role, for the common cases; anything not listed
+    // here falls back to 'generic'
+    const IMPLICIT_ROLES = {
+        a: 'link', button: 'button', nav: 'navigation', main: 'main',
+        header: 'banner', footer: 'contentinfo', aside: 'complementary',
+        article: 'article', section: 'region', form: 'form',
+        h1: 'heading', h2: 'heading', h3: 'heading', h4: 'heading',
+        h5: 'heading', h6: 'heading', img: 'img', ul: 'list', ol: 'list',
+        li: 'listitem', table: 'table', textarea: 'textbox', select: 'listbox',
+    };
+
+    // Resolve an element's implicit role, special-casing the handful of tags
+    // whose role depends on an attribute rather than just the tag name
+    function getImplicitRole(element) {
+        const tag = element.tagName.toLowerCase();
+        if (tag === 'a') {
+            return element.hasAttribute('href') ? 'link' : 'generic';
+        }
+        if (tag === 'input') {
+            const type = (element.getAttribute('type') || 'text').toLowerCase();
+            const INPUT_ROLES = {
+                checkbox: 'checkbox', radio: 'radio', button: 'button',
+                submit: 'button', range: 'slider', search: 'searchbox',
+            };
+            return INPUT_ROLES[type] || 'textbox';
+        }
+        if (tag === 'img') {
+            return element.getAttribute('alt') === '' ? 'presentation' : 'img';
+        }
+        return IMPLICIT_ROLES[tag] || 'generic';
+    }
+
+    // An explicit `role` attribute always wins over the implicit one
+    function getRole(element) {
+        return element.getAttribute('role') || getImplicitRole(element);
+    }
+
+    // Compute the accessible name following the standard precedence:
+    // aria-labelledby, then aria-label, then a native label/alt/title, then
+    // the element's own text content
+    function getAccessibleName(element) {
+        const labelledBy = element.getAttribute('aria-labelledby');
+        if (labelledBy) {
+            const names = labelledBy.split(/\s+/)
+                .map(id => document.getElementById(id))
+                .filter(Boolean)
+                .map(el => el.innerText || el.textContent || '');
+            if (names.length > 0) {
+                return names.join(' ').trim();
+            }
+        }
+
+        const label = element.getAttribute('aria-label');
+        if (label) {
+            return label.trim();
+        }
+
+        if (element.id) {
+            const associatedLabel = document.querySelector(`label[for="${element.id}"]`);
+            if (associatedLabel) {
+                return (associatedLabel.innerText || associatedLabel.textContent || '').trim();
+            }
+        }
+
+        const alt = element.getAttribute('alt');
+        if (alt) {
+            return alt.trim();
+        }
+
+        const title = element.getAttribute('title');
+        if (title) {
+            return title.trim();
+        }
+
+        return (element.innerText || element.textContent || '').trim();
+    }
+
+    // Parse `aria-owns` into the list of element ids it relocates into this
+    // element's accessibility subtree
+    function getOwnedChildren(element) {
+        const owns = element.getAttribute('aria-owns');
+        return owns ? owns.split(/\s+/).filter(Boolean) : [];
+    }
+
+    function nthChildOf(element) {
+        return element.parentElement
+            ? Array.from(element.parentElement.children).indexOf(element) + 1
+            : 1;
+    }
+
+    // Walk up from `element`'s parent, stopping at the nearest ancestor
+    // with an id (it anchors a short absolute selector) or the document
+    // root, so `generate_selector` on the Rust side can rebuild the full
+    // `id/tag.class/:nth-child(n)` chain down to this element
+    function getAncestorChain(element) {
+        const chain = [];
+        let current = element.parentElement;
+        while (current) {
+            chain.unshift({
+                tagName: current.tagName.toLowerCase(),
+                id: current.id,
+                classes: Array.from(current.classList),
+                nthChild: nthChildOf(current),
+            });
+            if (current.id) {
+                break;
+            }
+            current = current.parentElement;
+        }
+        return chain;
+    }
+
     // Function to gather element information
     function getElementInfo(element) {
         return {
@@ -162,6 +596,11 @@ const INSPECT_ELEMENT_JS: &str = r#"
             ),
             innerText: element.innerText,
             childrenCount: element.children.length,
+            role: getRole(element),
+            accessibleName: getAccessibleName(element),
+            ownedChildren: getOwnedChildren(element),
+            nthChild: nthChildOf(element),
+            ancestors: getAncestorChain(element),
         };
     }
 
@@ -174,42 +613,146 @@ const INSPECT_ELEMENT_JS: &str = r#"
         element.style.outline = '2px solid #ff0000';
     }
 
+    // Awaitable lookup of an element's cached computed styles
+    window.getComputedStylesFor = function(elementId) {
+        return invoke('get_computed_styles', { elementId });
+    };
+
     // Function to send element information to Rust
-    function sendElementInfo(element) {
+    async function sendElementInfo(element) {
         const elementInfo = getElementInfo(element);
-        window.ipc.postMessage(JSON.stringify({
-            action: 'select_element',
-            element: elementInfo,
-        }));
+        await invoke('select_element', { element: elementInfo });
 
         // Cache computed styles
-        window.ipc.postMessage(JSON.stringify({
-            action: 'cache_computed_styles',
-            elementId: elementInfo.id || `${elementInfo.tagName}-${Date.now()}`,
-            styles: elementInfo.computedStyles,
-        }));
+        const elementId = elementInfo.id || `${elementInfo.tagName}-${Date.now()}`;
+        await invoke('cache_computed_styles', { elementId, styles: elementInfo.computedStyles });
+    }
+
+    // Inspect mode is off by default so the page behaves like a normal
+    // browser; it's toggled on via keyboard shortcut and only then does the
+    // right-click context menu offer inspection actions
+    let inspectModeEnabled = false;
+    let contextMenuEl = null;
+
+    function removeContextMenu() {
+        if (contextMenuEl) {
+            contextMenuEl.remove();
+            contextMenuEl = null;
+        }
+    }
+
+    // Render the small overlay menu at the click position, offering actions
+    // that operate on `element`
+    function showContextMenu(x, y, element) {
+        removeContextMenu();
+
+        const menu = document.createElement('div');
+        menu.style.cssText = 'position:fixed;z-index:2147483647;background:#222;color:#eee;' +
+            'font:12px sans-serif;border:1px solid #444;border-radius:4px;padding:4px 0;' +
+            'min-width:140px;box-shadow:0 2px 8px rgba(0,0,0,0.4);';
+        menu.style.left = x + 'px';
+        menu.style.top = y + 'px';
+
+        const addItem = (label, handler) => {
+            const item = document.createElement('div');
+            item.textContent = label;
+            item.style.cssText = 'padding:4px 12px;cursor:pointer;';
+            item.addEventListener('mouseenter', () => { item.style.background = '#333'; });
+            item.addEventListener('mouseleave', () => { item.style.background = ''; });
+            item.addEventListener('click', function(event) {
+                event.stopPropagation();
+                removeContextMenu();
+                handler();
+            });
+            menu.appendChild(item);
+        };
+
+        addItem('Inspect Element', () => {
+            highlightElement(element);
+            sendElementInfo(element).catch(err => console.error('Aluminum inspect error:', err));
+        });
+        addItem('Copy Selector', () => {
+            sendElementInfo(element)
+                .then(() => invoke('copy_selector', {}))
+                .then(result => console.log('Copied selector to clipboard:', result.copied))
+                .catch(err => console.error('Aluminum copy selector error:', err));
+        });
+        addItem('Copy Styles', () => {
+            sendElementInfo(element)
+                .then(() => invoke('copy_styles', {}))
+                .then(result => console.log('Copied styles to clipboard:', result.copied))
+                .catch(err => console.error('Aluminum copy styles error:', err));
+        });
+        addItem('Select Parent', () => {
+            if (element.parentElement) {
+                showContextMenu(x, y, element.parentElement);
+            }
+        });
+
+        document.body.appendChild(menu);
+        contextMenuEl = menu;
     }
 
-    // Add click event listener to the document
-    document.addEventListener('click', function(event) {
+    // Suppress the native context menu and show the inspection overlay, but
+    // only while inspect mode is on, so normal browsing is unaffected
+    document.addEventListener('contextmenu', function(event) {
+        if (!inspectModeEnabled) {
+            return;
+        }
         event.preventDefault();
-        const element = event.target;
-        highlightElement(element);
-        sendElementInfo(element);
+        showContextMenu(event.clientX, event.clientY, event.target);
     }, true);
 
-    // Add keyboard shortcut to go back in history (Ctrl+Z)
+    document.addEventListener('click', function() {
+        removeContextMenu();
+    });
+
+    // Record link activation in the page-navigation history (kept separate
+    // from element-selection history), without interfering with the click
+    function notifyLinkActivation(event) {
+        const link = event.target.closest && event.target.closest('a[href]');
+        if (link) {
+            invoke('navigate', { url: link.href }).catch(err => console.error('Aluminum navigate error:', err));
+        }
+    }
+    document.addEventListener('click', notifyLinkActivation);
+
+    // Seed added PopStateEvent support for SPA routing, so record route
+    // changes the same way as full navigations
+    window.addEventListener('popstate', function() {
+        invoke('navigate', { url: window.location.href }).catch(err => console.error('Aluminum navigate error:', err));
+    });
+
+    // Add keyboard shortcuts: Ctrl+Shift+I toggles inspect mode, Ctrl+Z goes
+    // back in the selection history, Alt+Left/Alt+Right navigate the page
+    // history (Rust performs the actual `load_url` and replies with the URL)
     document.addEventListener('keydown', function(event) {
+        if (event.ctrlKey && event.shiftKey && event.key === 'I') {
+            inspectModeEnabled = !inspectModeEnabled;
+            invoke('set_inspect_mode', { enabled: inspectModeEnabled })
+                .then(() => console.log('Aluminum inspect mode:', inspectModeEnabled ? 'on' : 'off'))
+                .catch(err => console.error('Aluminum inspect mode toggle error:', err));
+        }
+
         if (event.ctrlKey && event.key === 'z') {
-            window.ipc.postMessage(JSON.stringify({
-                action: 'go_back',
-            }));
+            invoke('go_back', {})
+                .then(previousElement => console.log('Navigated back to element:', previousElement))
+                .catch(err => console.log('No previous element in history:', err.message));
+        }
+
+        if (event.altKey && event.key === 'ArrowLeft') {
+            invoke('go_back_page', {})
+                .then(result => console.log('Navigated back to page:', result.url))
+                .catch(err => console.log('No previous page in navigation history:', err.message));
+        }
+
+        if (event.altKey && event.key === 'ArrowRight') {
+            invoke('go_forward_page', {})
+                .then(result => console.log('Navigated forward to page:', result.url))
+                .catch(err => console.log('No next page in navigation history:', err.message));
         }
     });
 
     console.log('Aluminum Browser Inspect Element initialized');
 })();
 "#;
-
-// Include the JavaScript code in the binary
-include_str!("inspect_element.js");
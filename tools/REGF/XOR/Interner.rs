@@ -0,0 +1,41 @@
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Deduplicates repeated strings (CSS property names/values, attribute
+/// names/values, ...) into shared `Arc<str>` handles, the way rust-dominator
+/// interns DOM strings, so style-heavy pages don't pay for the same
+/// `"margin-top"`/`"rgb(0, 0, 0)"` bytes on every inspected element
+pub struct StringInterner {
+    strings: Mutex<HashMap<Arc<str>, ()>>,
+}
+
+impl StringInterner {
+    pub fn new() -> Self {
+        StringInterner { strings: Mutex::new(HashMap::new()) }
+    }
+
+    /// Return the shared handle for `s`, inserting it if this is the first
+    /// time it's been seen
+    pub fn intern(&self, s: &str) -> Arc<str> {
+        let mut strings = self.strings.lock().unwrap();
+        if let Some((existing, _)) = strings.get_key_value(s) {
+            return Arc::clone(existing);
+        }
+        let arc: Arc<str> = Arc::from(s);
+        strings.insert(Arc::clone(&arc), ());
+        arc
+    }
+
+    /// Drop every interned string with no remaining external references, so
+    /// the table doesn't grow without bound as inspected elements churn
+    pub fn sweep(&self) {
+        let mut strings = self.strings.lock().unwrap();
+        strings.retain(|s, _| Arc::strong_count(s) > 1);
+    }
+
+    /// Number of strings currently interned, for diagnostics
+    pub fn len(&self) -> usize {
+        self.strings.lock().unwrap().len()
+    }
+}
@@ -0,0 +1,52 @@
+
+/// Tracks visited page URLs independently of `Inspector`'s element-selection
+/// history, with a current index into the list so back/forward can move
+/// around without discarding the forward stack until a new URL is actually
+/// visited (the same shape as a real browser's navigation stack)
+pub struct NavigationHistory {
+    entries: Vec<String>,
+    current: Option<usize>,
+}
+
+impl NavigationHistory {
+    pub fn new() -> Self {
+        NavigationHistory {
+            entries: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Record a newly-visited URL, truncating any forward history past the
+    /// current position
+    pub fn visit(&mut self, url: &str) {
+        let insert_at = match self.current {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        self.entries.truncate(insert_at);
+        self.entries.push(url.to_string());
+        self.current = Some(insert_at);
+    }
+
+    /// Move one entry back, returning the URL to load, or `None` if already
+    /// at the start of history
+    pub fn go_back(&mut self) -> Option<String> {
+        let index = self.current?;
+        if index == 0 {
+            return None;
+        }
+        self.current = Some(index - 1);
+        self.entries.get(index - 1).cloned()
+    }
+
+    /// Move one entry forward, returning the URL to load, or `None` if
+    /// already at the end of history
+    pub fn go_forward(&mut self) -> Option<String> {
+        let index = self.current?;
+        if index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.current = Some(index + 1);
+        self.entries.get(index + 1).cloned()
+    }
+}
@@ -0,0 +1,150 @@
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+// Default schemes permitted when no explicit scheme list is configured
+const DEFAULT_ALLOWED_SCHEMES: &[&str] = &["http", "https"];
+
+/// How a host ended up permitted, attached to recorded network requests so
+/// `get_network_requests_summary` can flag user-approved vs. allowlisted traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PermissionGrant {
+    Allowlisted,
+    UserApproved,
+}
+
+/// Error returned when a request is blocked by the permission sandbox
+#[derive(Debug)]
+pub enum PermissionError {
+    PermissionDenied { host: String, scheme: String },
+}
+
+impl std::fmt::Display for PermissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PermissionError::PermissionDenied { host, scheme } => {
+                write!(f, "permission denied for {}://{}", scheme, host)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PermissionError {}
+
+/// Host-permission sandbox gating every outbound request, modeled on Deno's
+/// allow/deny flag model: an allowlist and denylist of host patterns
+/// (e.g. `*.example.com`) plus a set of allowed schemes
+#[derive(Debug, Clone)]
+pub struct Permissions {
+    allowlist: Vec<String>,
+    denylist: Vec<String>,
+    allowed_schemes: Vec<String>,
+    interactive: bool,
+    decisions: Arc<Mutex<HashMap<String, PermissionGrant>>>,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Permissions {
+            allowlist: Vec::new(),
+            denylist: Vec::new(),
+            allowed_schemes: DEFAULT_ALLOWED_SCHEMES.iter().map(|s| s.to_string()).collect(),
+            interactive: false,
+            decisions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl Permissions {
+    /// Create a permission sandbox with no explicit allow/deny rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a host pattern to the allowlist (`*.example.com` matches any subdomain)
+    pub fn allow_host(&mut self, pattern: &str) {
+        self.allowlist.push(pattern.to_string());
+    }
+
+    /// Add a host pattern to the denylist
+    pub fn deny_host(&mut self, pattern: &str) {
+        self.denylist.push(pattern.to_string());
+    }
+
+    /// Enable interactive prompting for hosts matched by neither list
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
+    fn host_matches(pattern: &str, host: &str) -> bool {
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host == suffix || host.ends_with(&format!(".{}", suffix)),
+            None => pattern == host,
+        }
+    }
+
+    /// Check whether a request to `url` is permitted. On a disallowed host
+    /// returns `PermissionDenied`. An empty allowlist permits any host not
+    /// explicitly denylisted; a non-empty allowlist requires a match, or
+    /// (when interactive mode is on) prompts the user. An "always" answer is
+    /// cached for the host; "y" permits only this request and any other
+    /// answer denies it, so both prompt again on the next request.
+    pub fn check(&self, url: &str) -> Result<PermissionGrant, PermissionError> {
+        let deny_err = |host: String, scheme: String| PermissionError::PermissionDenied { host, scheme };
+
+        let parsed = Url::parse(url).map_err(|_| deny_err(url.to_string(), String::new()))?;
+        let scheme = parsed.scheme().to_string();
+        let host = parsed.host_str().unwrap_or("").to_string();
+
+        if !self.allowed_schemes.iter().any(|s| s == &scheme) {
+            return Err(deny_err(host, scheme));
+        }
+
+        if let Some(grant) = self.decisions.lock().unwrap().get(&host) {
+            return Ok(*grant);
+        }
+
+        if self.denylist.iter().any(|pattern| Self::host_matches(pattern, &host)) {
+            return Err(deny_err(host, scheme));
+        }
+
+        if self.allowlist.iter().any(|pattern| Self::host_matches(pattern, &host)) {
+            let grant = PermissionGrant::Allowlisted;
+            self.decisions.lock().unwrap().insert(host, grant);
+            return Ok(grant);
+        }
+
+        // An empty allowlist means "no restriction" (the denylist is what
+        // opts a host out), the same convention `host_allowed` uses for
+        // SavePageAsHtml's domain lists - otherwise every request is denied
+        // out of the box with no lists configured and interactive mode off
+        if self.allowlist.is_empty() {
+            let grant = PermissionGrant::Allowlisted;
+            self.decisions.lock().unwrap().insert(host, grant);
+            return Ok(grant);
+        }
+
+        if self.interactive {
+            print!("Allow Aluminum to fetch {}? [y/n/always]: ", host);
+            io::stdout().flush().ok();
+
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer).ok();
+
+            return match answer.trim() {
+                "y" => Ok(PermissionGrant::UserApproved),
+                "always" => {
+                    let grant = PermissionGrant::UserApproved;
+                    self.decisions.lock().unwrap().insert(host, grant);
+                    Ok(grant)
+                }
+                _ => Err(deny_err(host, scheme)),
+            };
+        }
+
+        Err(deny_err(host, scheme))
+    }
+}
@@ -1,7 +1,9 @@
 
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+use regex::Regex;
 use reqwest;
 use scraper::{Html, Selector};
 use url::Url;
@@ -11,13 +13,25 @@ use base64;
 use image;
 use tokio;
 
+// Total bytes of recursively-inlined CSS resources (fonts, backgrounds, @imports)
+// we'll embed before we start leaving further references untouched
+const MAX_TOTAL_INLINE_BYTES: usize = 20 * 1024 * 1024;
+
 // Configuration struct for the HTML saving process
-struct SaveConfig {
-    include_styles: bool,
-    include_scripts: bool,
-    embed_images: bool,
-    minify: bool,
-    add_timestamp: bool,
+pub struct SaveConfig {
+    pub include_styles: bool,
+    pub include_scripts: bool,
+    pub embed_images: bool,
+    pub minify: bool,
+    pub add_timestamp: bool,
+    // Origins allowed/denied when embedding external resources. An empty
+    // allowlist means "no restriction"; the blocklist always wins.
+    pub domain_allowlist: Vec<String>,
+    pub domain_blocklist: Vec<String>,
+    pub no_images: bool,
+    pub no_fonts: bool,
+    pub no_js: bool,
+    pub no_css: bool,
 }
 
 impl Default for SaveConfig {
@@ -28,10 +42,43 @@ impl Default for SaveConfig {
             embed_images: true,
             minify: false,
             add_timestamp: true,
+            domain_allowlist: Vec::new(),
+            domain_blocklist: Vec::new(),
+            no_images: false,
+            no_fonts: false,
+            no_js: false,
+            no_css: false,
         }
     }
 }
 
+// Whether `host` is `suffix` itself or a subdomain of it, not just any host
+// sharing a trailing-character run (e.g. `suffix = "ads.com"` must not also
+// match `"myads.com"`)
+fn host_matches(host: &str, suffix: &str) -> bool {
+    host == suffix || host.ends_with(&format!(".{}", suffix))
+}
+
+// Whether a resource's host is permitted to be embedded under the current
+// config: the blocklist always wins, an empty allowlist permits every other
+// host, and a non-empty allowlist must otherwise match
+fn host_allowed(resource_url: &Url, config: &SaveConfig) -> bool {
+    let host = match resource_url.host_str() {
+        Some(host) => host,
+        None => return true,
+    };
+
+    if config.domain_blocklist.iter().any(|blocked| host_matches(host, blocked)) {
+        return false;
+    }
+
+    if config.domain_allowlist.is_empty() {
+        return true;
+    }
+
+    config.domain_allowlist.iter().any(|allowed| host_matches(host, allowed))
+}
+
 // Main function to save a page as HTML
 pub async fn save_page_as_html(url: &str, output_path: &str, config: SaveConfig) -> Result<(), Box<dyn std::error::Error>> {
     // Fetch the HTML content
@@ -61,16 +108,16 @@ async fn fetch_html_content(url: &str) -> Result<String, reqwest::Error> {
 async fn process_html(document: &Html, base_url: &str, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
     let mut processed_html = document.root_element().html();
 
-    if config.include_styles {
-        processed_html = process_styles(processed_html, base_url).await?;
+    if config.include_styles && !config.no_css {
+        processed_html = process_styles(processed_html, base_url, config).await?;
     }
 
-    if config.include_scripts {
-        processed_html = process_scripts(processed_html, base_url).await?;
+    if config.include_scripts && !config.no_js {
+        processed_html = process_scripts(processed_html, base_url, config).await?;
     }
 
-    if config.embed_images {
-        processed_html = process_images(processed_html, base_url).await?;
+    if config.embed_images && !config.no_images {
+        processed_html = process_images(processed_html, base_url, config).await?;
     }
 
     if config.minify {
@@ -84,17 +131,57 @@ async fn process_html(document: &Html, base_url: &str, config: &SaveConfig) -> R
     Ok(processed_html)
 }
 
-// Process and inline CSS styles
-async fn process_styles(html: String, base_url: &str) -> Result<String, Box<dyn std::error::Error>> {
+// Resolve the document's effective base URL, honoring a <base href> element
+// over the page's own URL, as browsers do when resolving relative links
+fn resolve_base_url(document: &Html, fallback: &str) -> Result<Url, Box<dyn std::error::Error>> {
+    let fallback_url = Url::parse(fallback)?;
+    let base_selector = Selector::parse("base[href]").unwrap();
+
+    if let Some(base_element) = document.select(&base_selector).next() {
+        if let Some(href) = base_element.value().attr("href") {
+            if let Ok(base) = fallback_url.join(href) {
+                return Ok(base);
+            }
+        }
+    }
+
+    Ok(fallback_url)
+}
+
+// Whether a resource path looks like a web font, so `no_fonts` can exclude
+// fonts specifically without dropping CSS background images too
+fn is_font_resource(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    [".woff2", ".woff", ".ttf", ".otf", ".eot"]
+        .iter()
+        .any(|ext| lower.contains(ext))
+}
+
+// Process and inline CSS styles, recursively resolving every `url(...)`
+// reference and `@import` each stylesheet pulls in so the saved page needs
+// no network access at all. Resources whose host fails `domain_allowlist`/
+// `domain_blocklist`, or fonts when `no_fonts` is set, are left unembedded.
+async fn process_styles(html: String, base_url: &str, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
     let document = Html::parse_document(&html);
     let style_selector = Selector::parse("link[rel='stylesheet']").unwrap();
+    let base = resolve_base_url(&document, base_url)?;
 
     let mut inline_styles = String::new();
+    let mut total_size = 0usize;
     for element in document.select(&style_selector) {
         if let Some(href) = element.value().attr("href") {
-            let style_url = Url::parse(base_url)?.join(href)?;
+            let style_url = base.join(href)?;
+            if !host_allowed(&style_url, config) {
+                continue; // leave the original <link rel="stylesheet"> reference untouched
+            }
+
             let style_content = fetch_html_content(style_url.as_str()).await?;
-            inline_styles.push_str(&format!("<style>{}</style>", style_content));
+
+            let mut visited = HashSet::new();
+            visited.insert(style_url.to_string());
+            let inlined = inline_css_resources(style_content, style_url, visited, &mut total_size, config).await?;
+
+            inline_styles.push_str(&format!("<style>{}</style>", inlined));
         }
     }
 
@@ -102,33 +189,131 @@ async fn process_styles(html: String, base_url: &str) -> Result<String, Box<dyn
     Ok(processed_html)
 }
 
-// Process and inline JavaScript
-async fn process_scripts(html: String, base_url: &str) -> Result<String, Box<dyn std::error::Error>> {
+// Recursively resolve `url(...)` references and `@import` rules inside a
+// stylesheet, replacing each with a `data:` URI. `visited` guards against
+// `@import` cycles and `total_size` caps the total bytes we'll embed.
+fn inline_css_resources<'a>(
+    css: String,
+    base_url: Url,
+    mut visited: HashSet<String>,
+    total_size: &'a mut usize,
+    config: &'a SaveConfig,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String, Box<dyn std::error::Error>>> + 'a>> {
+    Box::pin(async move {
+        let import_re = Regex::new(r#"@import\s+(?:url\()?["']?([^"')]+)["']?\)?;?"#)?;
+        let mut css = css;
+
+        for capture in import_re.captures_iter(&css.clone()).collect::<Vec<_>>() {
+            let import_ref = &capture[1];
+            let import_url = base_url.join(import_ref)?;
+
+            if !host_allowed(&import_url, config) {
+                continue; // leave the @import rule pointing at the original URL
+            }
+            if !visited.insert(import_url.to_string()) {
+                continue; // already inlined this stylesheet, avoid an @import loop
+            }
+            if *total_size >= MAX_TOTAL_INLINE_BYTES {
+                continue; // hit the archive size cap; leave the @import rule as-is
+            }
+
+            let imported_css = fetch_html_content(import_url.as_str()).await?;
+            *total_size += imported_css.len();
+            let inlined = inline_css_resources(imported_css, import_url, visited.clone(), total_size, config).await?;
+            css = css.replace(&capture[0], &inlined);
+        }
+
+        let url_re = Regex::new(r#"url\(\s*["']?([^"')]+)["']?\s*\)"#)?;
+        let mut replacements = Vec::new();
+        for capture in url_re.captures_iter(&css.clone()).collect::<Vec<_>>() {
+            let resource_ref = &capture[1];
+            if resource_ref.starts_with("data:") {
+                continue;
+            }
+            if config.no_fonts && is_font_resource(resource_ref) {
+                continue;
+            }
+
+            if *total_size >= MAX_TOTAL_INLINE_BYTES {
+                continue; // hit the archive size cap; leave remaining references as-is
+            }
+
+            let resource_url = base_url.join(resource_ref)?;
+            if !host_allowed(&resource_url, config) {
+                continue;
+            }
+
+            let bytes = fetch_image_content(resource_url.as_str()).await?;
+            *total_size += bytes.len();
+
+            let mime_type = from_path(resource_ref).first_or_octet_stream().to_string();
+            let data_url = format!("data:{};base64,{}", mime_type, base64::encode(&bytes));
+            replacements.push((capture[0].to_string(), format!("url(\"{}\")", data_url)));
+        }
+
+        for (original, replacement) in replacements {
+            css = css.replace(&original, &replacement);
+        }
+
+        Ok(css)
+    })
+}
+
+// Process and inline JavaScript. Scripts whose host fails the allow/block
+// lists are dropped from the output entirely rather than left dangling.
+async fn process_scripts(html: String, base_url: &str, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
     let document = Html::parse_document(&html);
     let script_selector = Selector::parse("script[src]").unwrap();
+    let base = resolve_base_url(&document, base_url)?;
 
     let mut inline_scripts = String::new();
+    let mut processed_html = html.clone();
     for element in document.select(&script_selector) {
         if let Some(src) = element.value().attr("src") {
-            let script_url = Url::parse(base_url)?.join(src)?;
+            let script_url = base.join(src)?;
+            if !host_allowed(&script_url, config) {
+                processed_html = processed_html.replace(&element.html(), "");
+                continue;
+            }
+
             let script_content = fetch_html_content(script_url.as_str()).await?;
             inline_scripts.push_str(&format!("<script>{}</script>", script_content));
         }
     }
 
-    let processed_html = html.replace("</body>", &format!("{}</body>", inline_scripts));
+    let processed_html = processed_html.replace("</body>", &format!("{}</body>", inline_scripts));
     Ok(processed_html)
 }
 
-// Process and embed images
-async fn process_images(html: String, base_url: &str) -> Result<String, Box<dyn std::error::Error>> {
+// Process and embed images: <img src>, <img srcset>/<source srcset>
+// candidates, and <link rel="icon"> favicons. Resources whose host fails
+// the allow/block lists are left as unembedded references.
+async fn process_images(html: String, base_url: &str, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
     let document = Html::parse_document(&html);
-    let img_selector = Selector::parse("img[src]").unwrap();
+    let base = resolve_base_url(&document, base_url)?;
 
     let mut processed_html = html.clone();
+    processed_html = inline_img_src(&document, &base, processed_html, config).await?;
+    processed_html = inline_srcset_candidates(&document, &base, processed_html, config).await?;
+    processed_html = inline_favicons(&document, &base, processed_html, config).await?;
+
+    Ok(processed_html)
+}
+
+// Embed every plain <img src>
+async fn inline_img_src(document: &Html, base: &Url, mut processed_html: String, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
+    let img_selector = Selector::parse("img[src]").unwrap();
     for element in document.select(&img_selector) {
         if let Some(src) = element.value().attr("src") {
-            let img_url = Url::parse(base_url)?.join(src)?;
+            if src.starts_with("data:") {
+                continue;
+            }
+
+            let img_url = base.join(src)?;
+            if !host_allowed(&img_url, config) {
+                continue;
+            }
+
             let img_content = fetch_image_content(img_url.as_str()).await?;
             let img_base64 = base64::encode(&img_content);
             let mime_type = from_path(src).first_or_octet_stream().to_string();
@@ -140,6 +325,62 @@ async fn process_images(html: String, base_url: &str) -> Result<String, Box<dyn
     Ok(processed_html)
 }
 
+// Expand `srcset`/`<source srcset>` candidate lists by picking the first
+// listed candidate and embedding it, since a saved page can't re-negotiate
+// DPR/viewport width the way a live browser would
+async fn inline_srcset_candidates(document: &Html, base: &Url, mut processed_html: String, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
+    let srcset_selector = Selector::parse("img[srcset], source[srcset]").unwrap();
+    for element in document.select(&srcset_selector) {
+        if let Some(srcset) = element.value().attr("srcset") {
+            let candidate = srcset
+                .split(',')
+                .next()
+                .and_then(|c| c.trim().split_whitespace().next())
+                .unwrap_or("");
+
+            if candidate.is_empty() || candidate.starts_with("data:") {
+                continue;
+            }
+
+            let candidate_url = base.join(candidate)?;
+            if !host_allowed(&candidate_url, config) {
+                continue;
+            }
+
+            let img_content = fetch_image_content(candidate_url.as_str()).await?;
+            let mime_type = from_path(candidate).first_or_octet_stream().to_string();
+            let data_url = format!("data:{};base64,{}", mime_type, base64::encode(&img_content));
+            processed_html = processed_html.replace(srcset, &data_url);
+        }
+    }
+
+    Ok(processed_html)
+}
+
+// Inline <link rel="icon"> favicons so the saved page still shows a tab icon offline
+async fn inline_favicons(document: &Html, base: &Url, mut processed_html: String, config: &SaveConfig) -> Result<String, Box<dyn std::error::Error>> {
+    let icon_selector = Selector::parse("link[rel~='icon']").unwrap();
+    for element in document.select(&icon_selector) {
+        if let Some(href) = element.value().attr("href") {
+            if href.starts_with("data:") {
+                continue;
+            }
+
+            let icon_url = base.join(href)?;
+            if !host_allowed(&icon_url, config) {
+                continue;
+            }
+
+            let icon_content = fetch_image_content(icon_url.as_str()).await?;
+            let mime_type = from_path(href).first_or_octet_stream().to_string();
+            let data_url = format!("data:{};base64,{}", mime_type, base64::encode(&icon_content));
+            processed_html = processed_html.replace(href, &data_url);
+        }
+    }
+
+    Ok(processed_html)
+}
+
 // Fetch image content
 async fn fetch_image_content(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
@@ -190,6 +431,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         embed_images: true,
         minify: false,
         add_timestamp: true,
+        domain_allowlist: Vec::new(),
+        domain_blocklist: vec!["doubleclick.net".to_string(), "google-analytics.com".to_string()],
+        no_images: false,
+        no_fonts: false,
+        no_js: false,
+        no_css: false,
     };
 
     save_page_as_html(url, output_path, config).await?;
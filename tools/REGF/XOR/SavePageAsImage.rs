@@ -1,10 +1,14 @@
 
+use std::collections::HashMap;
 use std::fs;
+use std::fs::File;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
-use image::{ImageBuffer, Rgba};
+use ego_tree::NodeId;
+use epub_builder::{EpubBuilder, EpubContent, EpubVersion, ReferenceType, ZipLibrary};
+use regex::Regex;
 use reqwest;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use headless_chrome::{Browser, LaunchOptions};
 use base64;
 use serde_json;
@@ -14,6 +18,38 @@ const DEFAULT_SAVE_PATH: &str = "./saved_pages";
 const DEFAULT_IMAGE_FORMAT: &str = "png";
 const DEFAULT_VIEWPORT_WIDTH: u32 = 1920;
 const DEFAULT_VIEWPORT_HEIGHT: u32 = 1080;
+const DEFAULT_QUALITY: u8 = 90;
+const SUPPORTED_IMAGE_FORMATS: &[&str] = &["png", "jpeg", "webp", "avif"];
+
+// Regex matching class/id tokens that are almost never part of the article body
+const UNLIKELY_CANDIDATES_PATTERN: &str = r"(?i)comment|sidebar|footer|nav|promo|ad|share|header";
+
+/// Result of a readability-style extraction pass over a parsed document
+pub struct ArticleContent {
+    pub title: String,
+    pub byline: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Device emulation settings applied before capture, for retina (high-DPR)
+/// or mobile-viewport renderings
+#[derive(Debug, Clone)]
+pub struct DeviceEmulation {
+    width: u32,
+    height: u32,
+    device_scale_factor: f64,
+    mobile: bool,
+    user_agent: Option<String>,
+}
+
+/// Condition to wait on before capturing, so client-rendered/lazy-loaded
+/// content has a chance to settle
+#[derive(Debug, Clone)]
+pub enum WaitCondition {
+    NetworkIdle { timeout_ms: u64 },
+    Selector(String),
+}
 
 /// SavePageAsImage struct to encapsulate the functionality
 pub struct SavePageAsImage {
@@ -21,6 +57,9 @@ pub struct SavePageAsImage {
     image_format: String,
     viewport_width: u32,
     viewport_height: u32,
+    quality: u8,
+    emulation: Option<DeviceEmulation>,
+    wait_condition: Option<WaitCondition>,
 }
 
 impl SavePageAsImage {
@@ -31,6 +70,9 @@ impl SavePageAsImage {
             image_format: DEFAULT_IMAGE_FORMAT.to_string(),
             viewport_width: DEFAULT_VIEWPORT_WIDTH,
             viewport_height: DEFAULT_VIEWPORT_HEIGHT,
+            quality: DEFAULT_QUALITY,
+            emulation: None,
+            wait_condition: None,
         }
     }
 
@@ -39,9 +81,32 @@ impl SavePageAsImage {
         self.save_path = path.to_string();
     }
 
-    /// Set custom image format
-    pub fn set_image_format(&mut self, format: &str) {
-        self.image_format = format.to_string();
+    /// The directory outputs are currently saved under
+    pub fn save_path(&self) -> &str {
+        &self.save_path
+    }
+
+    /// Set custom image format, validated against the formats we actually
+    /// know how to capture/encode so a typo errors instead of silently
+    /// writing a mislabeled PNG
+    pub fn set_image_format(&mut self, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let format = format.to_lowercase();
+        if !SUPPORTED_IMAGE_FORMATS.contains(&format.as_str()) {
+            return Err(format!(
+                "unsupported image format '{}', expected one of: {}",
+                format,
+                SUPPORTED_IMAGE_FORMATS.join(", ")
+            )
+            .into());
+        }
+
+        self.image_format = format;
+        Ok(())
+    }
+
+    /// Set the quality (0-100) used for lossy formats (JPEG/WebP)
+    pub fn set_quality(&mut self, quality: u8) {
+        self.quality = quality.min(100);
     }
 
     /// Set custom viewport dimensions
@@ -50,6 +115,137 @@ impl SavePageAsImage {
         self.viewport_height = height;
     }
 
+    /// Configure device emulation (retina DPR, mobile viewport, custom UA)
+    /// applied to the tab before every capture
+    pub fn set_device_emulation(
+        &mut self,
+        width: u32,
+        height: u32,
+        device_scale_factor: f64,
+        mobile: bool,
+        user_agent: Option<&str>,
+    ) {
+        self.emulation = Some(DeviceEmulation {
+            width,
+            height,
+            device_scale_factor,
+            mobile,
+            user_agent: user_agent.map(|ua| ua.to_string()),
+        });
+    }
+
+    /// Set a condition to wait on before capturing, so client-rendered or
+    /// lazy-loaded content is present in the screenshot
+    pub fn set_wait_condition(&mut self, condition: Option<WaitCondition>) {
+        self.wait_condition = condition;
+    }
+
+    /// Resolve the configured `image_format` string to an `image` crate
+    /// `ImageFormat`, used for re-encoding downloaded images
+    fn target_image_format(&self) -> Result<image::ImageFormat, Box<dyn std::error::Error>> {
+        match self.image_format.as_str() {
+            "png" => Ok(image::ImageFormat::Png),
+            "jpeg" => Ok(image::ImageFormat::Jpeg),
+            "webp" => Ok(image::ImageFormat::WebP),
+            "avif" => Ok(image::ImageFormat::Avif),
+            other => Err(format!("unsupported image format: {}", other).into()),
+        }
+    }
+
+    /// Capture a screenshot honoring the configured format/quality end to
+    /// end: JPEG/WebP are requested directly from CDP with a quality value,
+    /// while AVIF is captured as PNG and re-encoded through the `image` crate
+    /// since Chrome's screenshot capture doesn't support it natively
+    fn capture_screenshot_bytes(
+        &self,
+        tab: &headless_chrome::Tab,
+        capture_beyond_viewport: bool,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        use headless_chrome::protocol::cdp::Page;
+
+        let cdp_format = match self.image_format.as_str() {
+            "jpeg" => Page::CaptureScreenshotFormatOption::Jpeg,
+            "webp" => Page::CaptureScreenshotFormatOption::Webp,
+            _ => Page::CaptureScreenshotFormatOption::Png,
+        };
+
+        let quality = match self.image_format.as_str() {
+            "jpeg" | "webp" => Some(self.quality as u32),
+            _ => None,
+        };
+
+        let screenshot_data = tab.call_method(Page::CaptureScreenshot {
+            format: Some(cdp_format),
+            quality,
+            clip: None,
+            from_surface: Some(true),
+            capture_beyond_viewport: Some(capture_beyond_viewport),
+        })?;
+
+        let raw = base64::decode(&screenshot_data.data)?;
+
+        if self.image_format == "avif" {
+            let img = image::load_from_memory(&raw)?;
+            let mut buffer = Vec::new();
+            img.write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Avif)?;
+            Ok(buffer)
+        } else {
+            Ok(raw)
+        }
+    }
+
+    /// Apply the configured device emulation (if any) to a tab via CDP's
+    /// `Emulation.setDeviceMetricsOverride`
+    fn apply_emulation(&self, tab: &headless_chrome::Tab) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(emulation) = &self.emulation {
+            tab.call_method(headless_chrome::protocol::cdp::Emulation::SetDeviceMetricsOverride {
+                width: emulation.width,
+                height: emulation.height,
+                device_scale_factor: emulation.device_scale_factor,
+                mobile: emulation.mobile,
+                ..Default::default()
+            })?;
+
+            if let Some(user_agent) = &emulation.user_agent {
+                tab.set_user_agent(user_agent, None, None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Block until the configured wait condition is satisfied (or its
+    /// timeout elapses), giving lazy-loaded/client-rendered content a
+    /// chance to appear before capture
+    fn wait_for_ready(&self, tab: &headless_chrome::Tab) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.wait_condition {
+            Some(WaitCondition::Selector(selector)) => {
+                tab.wait_for_element(selector)?;
+            }
+            Some(WaitCondition::NetworkIdle { timeout_ms }) => {
+                let deadline = std::time::Instant::now() + std::time::Duration::from_millis(*timeout_ms);
+                let mut last_content_length = 0usize;
+                loop {
+                    let content_length = tab
+                        .evaluate("document.documentElement.outerHTML.length", false)?
+                        .value
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as usize;
+
+                    if content_length == last_content_length || std::time::Instant::now() >= deadline {
+                        break;
+                    }
+
+                    last_content_length = content_length;
+                    std::thread::sleep(std::time::Duration::from_millis(250));
+                }
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
     /// Save the webpage as an image
     pub fn save(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
         // Ensure save directory exists
@@ -63,19 +259,22 @@ impl SavePageAsImage {
 
         // Create a new page and navigate to the URL
         let tab = browser.new_tab()?;
+        self.apply_emulation(&tab)?;
         tab.navigate_to(url)?;
         tab.wait_until_navigated()?;
 
-        // Set viewport size
-        tab.set_viewport(self.viewport_width, self.viewport_height)?;
+        // Only fall back to the plain viewport when device emulation isn't
+        // configured: `set_viewport` re-issues its own device-metrics
+        // override with a default DPR/mobile flag, which would otherwise
+        // clobber whatever `apply_emulation` just set
+        if self.emulation.is_none() {
+            tab.set_viewport(self.viewport_width, self.viewport_height)?;
+        }
+
+        self.wait_for_ready(&tab)?;
 
-        // Capture screenshot
-        let screenshot = tab.capture_screenshot(
-            headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png,
-            None,
-            None,
-            true,
-        )?;
+        // Capture screenshot in the configured format
+        let screenshot = self.capture_screenshot_bytes(&tab, false)?;
 
         // Generate filename
         let filename = self.generate_filename(url);
@@ -127,7 +326,9 @@ impl SavePageAsImage {
         Ok(saved_images)
     }
 
-    /// Download and save an individual image
+    /// Download an individual image and re-encode it into the configured
+    /// output format (honoring newer codecs like WebP/AVIF) before saving,
+    /// using an explicit encoder for JPEG so `self.quality` is actually honored
     fn download_and_save_image(&self, img_url: &str) -> Result<String, Box<dyn std::error::Error>> {
         let response = reqwest::blocking::get(img_url)?;
         let img_content = response.bytes()?;
@@ -136,12 +337,21 @@ impl SavePageAsImage {
         let filename = self.generate_filename(img_url);
         let full_path = format!("{}/{}.{}", self.save_path, filename, self.image_format);
 
-        img.save_with_format(&full_path, image::ImageFormat::Png)?;
+        let mut file = File::create(&full_path)?;
+        match self.target_image_format()? {
+            image::ImageFormat::Jpeg => {
+                let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut file, self.quality);
+                img.write_with_encoder(encoder)?;
+            }
+            format => img.write_to(&mut file, format)?,
+        }
 
         Ok(full_path)
     }
 
-    /// Generate a full page screenshot by scrolling and stitching multiple screenshots
+    /// Generate a full page screenshot with a single native CDP capture
+    /// (`Page.captureScreenshot` with `captureBeyondViewport: true`), so
+    /// Chrome renders the entire page itself instead of us stitching tiles
     pub fn full_page_screenshot(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
         // Launch headless browser
         let browser = Browser::new(LaunchOptions {
@@ -151,45 +361,25 @@ impl SavePageAsImage {
 
         // Create a new page and navigate to the URL
         let tab = browser.new_tab()?;
+        self.apply_emulation(&tab)?;
         tab.navigate_to(url)?;
         tab.wait_until_navigated()?;
 
-        // Set initial viewport size
-        tab.set_viewport(self.viewport_width, self.viewport_height)?;
-
-        // Get full page height
-        let full_height: u32 = tab.evaluate("document.body.scrollHeight")?.value.unwrap().as_u64().unwrap() as u32;
-
-        // Calculate number of screenshots needed
-        let num_screenshots = (full_height as f32 / self.viewport_height as f32).ceil() as u32;
-
-        // Create a buffer to store the full page image
-        let mut full_page_buffer = ImageBuffer::new(self.viewport_width, full_height);
-
-        for i in 0..num_screenshots {
-            // Scroll to the appropriate position
-            let scroll_y = i * self.viewport_height;
-            tab.evaluate(&format!("window.scrollTo(0, {})", scroll_y))?;
+        // See `save`: skip the plain viewport when emulation already set
+        // device metrics, so it isn't immediately overwritten with defaults
+        if self.emulation.is_none() {
+            tab.set_viewport(self.viewport_width, self.viewport_height)?;
+        }
 
-            // Capture screenshot
-            let screenshot = tab.capture_screenshot(
-                headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png,
-                None,
-                None,
-                true,
-            )?;
+        self.wait_for_ready(&tab)?;
 
-            // Convert screenshot to image buffer
-            let img = image::load_from_memory(&screenshot)?;
-
-            // Copy screenshot to the appropriate position in the full page buffer
-            image::imageops::replace(&mut full_page_buffer, &img, 0, scroll_y);
-        }
+        // Capture the whole page in one native shot, in the configured format
+        let screenshot = self.capture_screenshot_bytes(&tab, true)?;
 
         // Generate filename and save the full page screenshot
         let filename = self.generate_filename(url);
         let full_path = format!("{}/{}_full.{}", self.save_path, filename, self.image_format);
-        full_page_buffer.save_with_format(&full_path, image::ImageFormat::Png)?;
+        fs::write(&full_path, screenshot)?;
 
         Ok(full_path)
     }
@@ -249,6 +439,280 @@ impl SavePageAsImage {
         Ok(full_path)
     }
 
+    /// Extract the main article from the webpage using a readability-style scoring
+    /// pass, and save it as plain text alongside a small metadata JSON.
+    pub fn save_article(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
+        // Ensure save directory exists
+        fs::create_dir_all(&self.save_path)?;
+
+        // Fetch the HTML content
+        let html_content = reqwest::blocking::get(url)?.text()?;
+        let article = self.extract_article(&html_content)?;
+
+        // Generate filename and save the extracted text
+        let filename = self.generate_filename(url);
+        let full_path = format!("{}/{}_article.txt", self.save_path, filename);
+        fs::write(&full_path, &article.text)?;
+
+        // Save the extracted title/byline alongside the cleaned HTML for archival
+        let metadata = serde_json::json!({
+            "url": url,
+            "title": article.title,
+            "byline": article.byline,
+            "html": article.html,
+        });
+        let metadata_path = format!("{}/{}_article_metadata.json", self.save_path, filename);
+        fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)?;
+
+        Ok(full_path)
+    }
+
+    /// Run the readability-style extraction pass over a parsed document: skip
+    /// unlikely candidates when scoring, score every other paragraph/div,
+    /// propagate scores to the parent (full value) and grandparent (half
+    /// value), then pick the highest-scoring node as the article root and fold
+    /// in high-scoring siblings. Unlikely candidates are not removed from the
+    /// emitted subtree: one nested inside the chosen root or a kept sibling
+    /// still appears in the returned text and HTML.
+    fn extract_article(&self, html_content: &str) -> Result<ArticleContent, Box<dyn std::error::Error>> {
+        let document = Html::parse_document(html_content);
+
+        let title = document
+            .select(&Selector::parse("title").unwrap())
+            .next()
+            .map(|t| t.inner_html())
+            .unwrap_or_default();
+
+        let byline = document
+            .select(&Selector::parse("meta[name='author']").unwrap())
+            .next()
+            .and_then(|m| m.value().attr("content"))
+            .unwrap_or_default()
+            .to_string();
+
+        let unlikely_candidates = Regex::new(UNLIKELY_CANDIDATES_PATTERN)?;
+        let candidate_selector = Selector::parse("p, div").unwrap();
+
+        let mut scores: HashMap<NodeId, f64> = HashMap::new();
+
+        for element in document.select(&candidate_selector) {
+            if self.is_unlikely_candidate(&element, &unlikely_candidates) {
+                continue;
+            }
+
+            let text = element.text().collect::<Vec<_>>().join(" ");
+            let text_len = text.trim().len();
+            if text_len == 0 {
+                continue;
+            }
+
+            let comma_count = text.matches(',').count();
+            let mut score = 1.0 + comma_count as f64 + (text_len as f64 / 100.0).min(3.0);
+            score *= 1.0 - self.link_density(&element);
+
+            *scores.entry(element.id()).or_insert(0.0) += score;
+
+            if let Some(parent) = element.parent().and_then(ElementRef::wrap) {
+                *scores.entry(parent.id()).or_insert(0.0) += score;
+
+                if let Some(grandparent) = parent.parent().and_then(ElementRef::wrap) {
+                    *scores.entry(grandparent.id()).or_insert(0.0) += score / 2.0;
+                }
+            }
+        }
+
+        let (top_id, top_score) = scores
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(id, score)| (*id, *score))
+            .ok_or("no article candidates found on page")?;
+
+        let threshold = (top_score * 0.2).max(10.0);
+        let root = ElementRef::wrap(document.tree.get(top_id).unwrap())
+            .ok_or("article candidate is not an element node")?;
+
+        let mut article_html = String::new();
+        let mut article_text = Vec::new();
+
+        match root.parent().and_then(ElementRef::wrap) {
+            Some(parent) => {
+                for sibling in parent.children().filter_map(ElementRef::wrap) {
+                    let sibling_score = scores.get(&sibling.id()).copied().unwrap_or(0.0);
+                    if sibling.id() == root.id() || sibling_score > threshold {
+                        article_html.push_str(&sibling.html());
+                        article_text.push(sibling.text().collect::<Vec<_>>().join(" "));
+                    }
+                }
+            }
+            None => {
+                article_html.push_str(&root.html());
+                article_text.push(root.text().collect::<Vec<_>>().join(" "));
+            }
+        }
+
+        Ok(ArticleContent {
+            title,
+            byline,
+            text: article_text.join("\n\n"),
+            html: article_html,
+        })
+    }
+
+    /// Check whether an element's `class`/`id` marks it as an unlikely candidate
+    /// (nav bars, ads, footers, etc.) that should be excluded from scoring.
+    fn is_unlikely_candidate(&self, element: &ElementRef, pattern: &Regex) -> bool {
+        let class_and_id = format!(
+            "{} {}",
+            element.value().attr("class").unwrap_or_default(),
+            element.value().attr("id").unwrap_or_default()
+        );
+        pattern.is_match(&class_and_id)
+    }
+
+    /// Fraction of an element's text that sits inside `<a>` tags
+    fn link_density(&self, element: &ElementRef) -> f64 {
+        let total_len = element.text().collect::<Vec<_>>().join("").len();
+        if total_len == 0 {
+            return 0.0;
+        }
+
+        let link_selector = Selector::parse("a").unwrap();
+        let link_len: usize = element
+            .select(&link_selector)
+            .map(|a| a.text().collect::<Vec<_>>().join("").len())
+            .sum();
+
+        link_len as f64 / total_len as f64
+    }
+
+    /// Build a single EPUB3 e-book from one URL's extracted article
+    pub fn save_as_epub(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
+        self.save_multiple_as_epub(&[url])
+    }
+
+    /// Build a single EPUB3 e-book merging several URLs' extracted articles,
+    /// one XHTML chapter per page, with a generated table of contents.
+    pub fn save_multiple_as_epub(&self, urls: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        fs::create_dir_all(&self.save_path)?;
+
+        let mut epub = EpubBuilder::new(ZipLibrary::new()?)?;
+        epub.epub_version(EpubVersion::V30);
+
+        let mut book_title = String::new();
+
+        for (index, url) in urls.iter().enumerate() {
+            let html_content = reqwest::blocking::get(*url)?.text()?;
+            let article = self.extract_article(&html_content)?;
+
+            if index == 0 {
+                book_title = article.title.clone();
+                epub.metadata("title", &book_title)?;
+                if !article.byline.is_empty() {
+                    epub.metadata("author", &article.byline)?;
+                }
+                epub.metadata("description", *url)?;
+            }
+
+            let (chapter_html, images) = self.embed_chapter_images(&article.html, *url, index)?;
+            for (image_path, image_data, media_type) in images {
+                epub.add_resource(&image_path, image_data.as_slice(), media_type)?;
+            }
+
+            let chapter_xhtml = format!(
+                "<html><head><title>{title}</title></head><body><h1>{title}</h1>{body}</body></html>",
+                title = article.title,
+                body = chapter_html,
+            );
+
+            let chapter_path = format!("chapter_{}.xhtml", index);
+            epub.add_content(
+                EpubContent::new(&chapter_path, chapter_xhtml.as_bytes())
+                    .title(&article.title)
+                    .reftype(ReferenceType::Text),
+            )?;
+
+            // Use the first page's screenshot as the cover
+            if index == 0 {
+                if let Ok(screenshot) = self.save(url) {
+                    if let Ok(cover_data) = fs::read(&screenshot) {
+                        // The screenshot is encoded in whatever `self.image_format`
+                        // is configured to, not necessarily PNG - sniff it the same
+                        // way `embed_chapter_images` does instead of assuming
+                        let (extension, media_type) = image::guess_format(&cover_data)
+                            .map(Self::image_media_type)
+                            .unwrap_or(("jpg", "image/jpeg"));
+                        let cover_name = format!("cover.{}", extension);
+                        epub.add_cover_image(&cover_name, cover_data.as_slice(), media_type)?;
+                    }
+                }
+            }
+        }
+
+        let filename = self.generate_filename(&urls.join(","));
+        let full_path = format!("{}/{}.epub", self.save_path, filename);
+        let mut epub_file = File::create(&full_path)?;
+        epub.generate(&mut epub_file)?;
+
+        Ok(full_path)
+    }
+
+    /// Map a sniffed `image::ImageFormat` to the file extension and EPUB
+    /// manifest media type it should be packaged under, falling back to JPEG
+    /// for anything the `image` crate doesn't specifically recognize
+    fn image_media_type(format: image::ImageFormat) -> (&'static str, &'static str) {
+        match format {
+            image::ImageFormat::Png => ("png", "image/png"),
+            image::ImageFormat::Jpeg => ("jpg", "image/jpeg"),
+            image::ImageFormat::Gif => ("gif", "image/gif"),
+            image::ImageFormat::WebP => ("webp", "image/webp"),
+            image::ImageFormat::Bmp => ("bmp", "image/bmp"),
+            image::ImageFormat::Avif => ("avif", "image/avif"),
+            _ => ("jpg", "image/jpeg"),
+        }
+    }
+
+    /// Download and embed every `<img>` referenced by a chapter's HTML,
+    /// rewriting `src` to the in-package resource path
+    fn embed_chapter_images(
+        &self,
+        chapter_html: &str,
+        base_url: &str,
+        chapter_index: usize,
+    ) -> Result<(String, Vec<(String, Vec<u8>, &'static str)>), Box<dyn std::error::Error>> {
+        let document = Html::parse_fragment(chapter_html);
+        let img_selector = Selector::parse("img").unwrap();
+
+        let mut rewritten = chapter_html.to_string();
+        let mut images = Vec::new();
+
+        for (image_index, img) in document.select(&img_selector).enumerate() {
+            if let Some(src) = img.value().attr("src") {
+                let img_url = if src.starts_with("http") {
+                    src.to_string()
+                } else {
+                    format!("{}{}", base_url, src)
+                };
+
+                if let Ok(response) = reqwest::blocking::get(&img_url) {
+                    if let Ok(bytes) = response.bytes() {
+                        // Sniff the real format from the bytes rather than
+                        // assuming JPEG, so the EPUB's declared media type
+                        // actually matches what's packaged
+                        let (extension, media_type) = image::guess_format(&bytes)
+                            .map(Self::image_media_type)
+                            .unwrap_or(("jpg", "image/jpeg"));
+                        let package_path =
+                            format!("images/ch{}_{}.{}", chapter_index, image_index, extension);
+                        rewritten = rewritten.replace(src, &package_path);
+                        images.push((package_path, bytes.to_vec(), media_type));
+                    }
+                }
+            }
+        }
+
+        Ok((rewritten, images))
+    }
+
     /// Save webpage metadata (title, description, keywords)
     pub fn save_metadata(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
         // Fetch the HTML content
@@ -296,6 +760,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut save_tool = SavePageAsImage::new();
     save_tool.set_save_path("./aluminum_saved_pages");
     save_tool.set_viewport(1440, 900);
+    save_tool.set_device_emulation(390, 844, 2.0, true, Some("Aluminum/1.0 (Mobile)"));
+    save_tool.set_wait_condition(Some(WaitCondition::NetworkIdle { timeout_ms: 2000 }));
+    save_tool.set_image_format("webp")?;
+    save_tool.set_quality(85);
 
     let url = "";
 
@@ -323,5 +791,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let metadata_path = save_tool.save_metadata(url)?;
     println!("Metadata saved: {}", metadata_path);
 
+    // Save the extracted article content
+    let article_path = save_tool.save_article(url)?;
+    println!("Article saved: {}", article_path);
+
+    // Save the page as an EPUB e-book
+    let epub_path = save_tool.save_as_epub(url)?;
+    println!("Page saved as EPUB: {}", epub_path);
+
     Ok(())
 }
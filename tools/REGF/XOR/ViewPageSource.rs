@@ -1,4 +1,5 @@
 
+use std::collections::HashMap;
 use std::io::{self, Write};
 use reqwest;
 use colored::*;
@@ -9,8 +10,12 @@ use std::default::Default;
 use std::fs::File;
 use std::path::Path;
 use std::time::Instant;
+use tokio::time::{sleep, Duration};
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::{check_permission, record_fetch};
+use crate::{CachedResponse, get_cached, store_cached, clear_cache, cache_stats, parse_max_age};
+
 // Constants for configuration
 const USER_AGENT: &str = "Aluminum/1.0";
 const TIMEOUT_SECONDS: u64 = 30;
@@ -22,38 +27,160 @@ struct PageSource {
     content: String,
     status_code: u16,
     headers: reqwest::header::HeaderMap,
+    from_cache: bool,
+}
+
+impl PageSource {
+    /// The response's `ETag` header, if present, used to debounce watch mode
+    fn etag(&self) -> Option<&str> {
+        self.headers.get(reqwest::header::ETAG).and_then(|v| v.to_str().ok())
+    }
+
+    /// Rebuild a `PageSource` from a cache entry, reconstituting the
+    /// `ETag`/`Last-Modified` headers so `etag()` keeps working unchanged
+    fn from_cache(url: &str, cached: CachedResponse) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(etag) = cached.etag.as_deref().and_then(|v| reqwest::header::HeaderValue::from_str(v).ok()) {
+            headers.insert(reqwest::header::ETAG, etag);
+        }
+        if let Some(last_modified) = cached.last_modified.as_deref().and_then(|v| reqwest::header::HeaderValue::from_str(v).ok()) {
+            headers.insert(reqwest::header::LAST_MODIFIED, last_modified);
+        }
+
+        PageSource {
+            url: url.to_string(),
+            content: cached.body,
+            status_code: cached.status,
+            headers,
+            from_cache: true,
+        }
+    }
 }
 
-/// Function to fetch the page source
-async fn fetch_page_source(url: &str) -> Result<PageSource, Box<dyn std::error::Error>> {
+/// Flatten a `reqwest::header::HeaderMap` into a plain string map for the
+/// network log, dropping any value that isn't valid UTF-8
+fn header_map_to_hashmap(headers: &reqwest::header::HeaderMap) -> HashMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
+        .collect()
+}
+
+/// Rebuild the small set of headers a cache entry remembers (`ETag`/`Last-Modified`)
+/// as a plain string map for the network log
+fn cached_response_headers(cached: &CachedResponse) -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    if let Some(etag) = &cached.etag {
+        headers.insert("etag".to_string(), etag.clone());
+    }
+    if let Some(last_modified) = &cached.last_modified {
+        headers.insert("last-modified".to_string(), last_modified.clone());
+    }
+    headers
+}
+
+/// Function to fetch the page source. Gated by DevMode's host-permission
+/// sandbox: a disallowed host returns a `PermissionDenied` error instead of
+/// proceeding, and the resolved grant is attached to DevMode's network log.
+/// Consults the conditional-request cache first: a fresh entry is returned
+/// with no network round trip, a stale one is revalidated with
+/// `If-None-Match`/`If-Modified-Since` and may come back as a `304`. Pass
+/// `force_revalidate` to skip the freshness short-circuit and always hit the
+/// network (still sending conditional headers, so an unchanged origin costs
+/// only a `304`) - `watch_page_source` needs this so it actually detects
+/// changes instead of replaying the first response for the whole `max-age` window.
+async fn fetch_page_source(url: &str, force_revalidate: bool) -> Result<PageSource, Box<dyn std::error::Error>> {
+    let grant = check_permission(url)?;
+
+    let cached = get_cached(url);
+    if !force_revalidate {
+        if let Some(cached) = &cached {
+            if cached.is_fresh() {
+                record_fetch(url, "GET", cached.status, grant, HashMap::new(), cached_response_headers(cached), Some(cached.body.clone()));
+                return Ok(PageSource::from_cache(url, cached.clone()));
+            }
+        }
+    }
+
     let client = reqwest::Client::builder()
         .user_agent(USER_AGENT)
         .timeout(std::time::Duration::from_secs(TIMEOUT_SECONDS))
         .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
         .build()?;
 
-    let response = client.get(url).send().await?;
+    let mut request = client.get(url);
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified.clone());
+        }
+    }
+
+    let response = request.send().await?;
     let status_code = response.status().as_u16();
+
+    if status_code == 304 {
+        let cached = cached.expect("304 response implies a cached entry to revalidate");
+        record_fetch(url, "GET", status_code, grant, HashMap::new(), cached_response_headers(&cached), Some(cached.body.clone()));
+        return Ok(PageSource::from_cache(url, cached));
+    }
+
     let headers = response.headers().clone();
     let content = response.text().await?;
 
+    let etag = headers.get(reqwest::header::ETAG).and_then(|v| v.to_str().ok()).map(String::from);
+    let last_modified = headers.get(reqwest::header::LAST_MODIFIED).and_then(|v| v.to_str().ok()).map(String::from);
+    let max_age = headers
+        .get(reqwest::header::CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_max_age);
+
+    store_cached(url, CachedResponse {
+        body: content.clone(),
+        status: status_code,
+        etag,
+        last_modified,
+        fetched_at: chrono::Utc::now(),
+        max_age,
+    });
+
+    record_fetch(url, "GET", status_code, grant, HashMap::new(), header_map_to_hashmap(&headers), Some(content.clone()));
+
     Ok(PageSource {
         url: url.to_string(),
         content,
         status_code,
         headers,
+        from_cache: false,
     })
 }
 
+/// Fetch a URL through the same permission-gated, cache-backed path as the
+/// viewer, returning just the status and body for callers (like the
+/// crawler) that don't need the full `PageSource`
+pub async fn fetch_url(url: &str) -> Result<(u16, String), Box<dyn std::error::Error>> {
+    let page_source = fetch_page_source(url, false).await?;
+    Ok((page_source.status_code, page_source.content))
+}
+
 /// Function to parse and pretty print HTML
 fn pretty_print_html(content: &str) -> String {
+    pretty_print_html_and_links(content).0
+}
+
+/// Pretty print HTML and collect every `<a href>` target seen during the
+/// same DOM walk, so a crawler can follow links without a second parse pass
+pub fn pretty_print_html_and_links(content: &str) -> (String, Vec<String>) {
     let mut pretty_html = String::new();
+    let mut links = Vec::new();
     let dom = parse_document(RcDom::default(), Default::default())
         .from_utf8()
         .read_from(&mut content.as_bytes())
         .unwrap();
 
-    fn walk(indent: usize, handle: &Handle, pretty_html: &mut String) {
+    fn walk(indent: usize, handle: &Handle, pretty_html: &mut String, links: &mut Vec<String>) {
         let node = handle;
         match node.data {
             NodeData::Element { ref name, ref attrs, .. } => {
@@ -61,10 +188,13 @@ fn pretty_print_html(content: &str) -> String {
                 pretty_html.push_str(&format!("<{}", name.local));
                 for attr in attrs.borrow().iter() {
                     pretty_html.push_str(&format!(" {}=\"{}\"", attr.name.local, attr.value));
+                    if name.local.as_ref() == "a" && attr.name.local.as_ref() == "href" {
+                        links.push(attr.value.to_string());
+                    }
                 }
                 pretty_html.push_str(">\n");
                 for child in node.children.borrow().iter() {
-                    walk(indent + 1, child, pretty_html);
+                    walk(indent + 1, child, pretty_html, links);
                 }
                 pretty_html.push_str(&"  ".repeat(indent));
                 pretty_html.push_str(&format!("</{}>\n", name.local));
@@ -80,12 +210,138 @@ fn pretty_print_html(content: &str) -> String {
         }
     }
 
-    walk(0, &dom.document, &mut pretty_html);
-    pretty_html
+    walk(0, &dom.document, &mut pretty_html, &mut links);
+    (pretty_html, links)
+}
+
+/// A single line-level diff operation between two versions of a pretty-printed document
+enum DiffLine<'a> {
+    Unchanged,
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compute a simple LCS-based line diff between two versions of pretty-printed HTML
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let (m, n) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; n + 1]; m + 1];
+
+    for i in (0..m).rev() {
+        for j in (0..n).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < m && j < n {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Unchanged);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    while i < m {
+        diff.push(DiffLine::Removed(old[i]));
+        i += 1;
+    }
+    while j < n {
+        diff.push(DiffLine::Added(new[j]));
+        j += 1;
+    }
+
+    diff
+}
+
+/// Print a line diff, collapsing unchanged regions and coloring removed
+/// lines red and added lines green, Deno-dev-tooling style
+fn print_diff(old_pretty: &str, new_pretty: &str) {
+    let old_lines: Vec<&str> = old_pretty.lines().collect();
+    let new_lines: Vec<&str> = new_pretty.lines().collect();
+    let diff = diff_lines(&old_lines, &new_lines);
+
+    let mut unchanged_run = 0usize;
+    let mut old_line_no = 1;
+    let mut new_line_no = 1;
+
+    for op in &diff {
+        match op {
+            DiffLine::Unchanged => {
+                unchanged_run += 1;
+                old_line_no += 1;
+                new_line_no += 1;
+            }
+            DiffLine::Removed(line) => {
+                if unchanged_run > 0 {
+                    println!("{}", format!("  ... {} unchanged line(s) ...", unchanged_run).dimmed());
+                    unchanged_run = 0;
+                }
+                println!("{}", format!("- {:>4} | {}", old_line_no, line).red());
+                old_line_no += 1;
+            }
+            DiffLine::Added(line) => {
+                if unchanged_run > 0 {
+                    println!("{}", format!("  ... {} unchanged line(s) ...", unchanged_run).dimmed());
+                    unchanged_run = 0;
+                }
+                println!("{}", format!("+ {:>4} | {}", new_line_no, line).green());
+                new_line_no += 1;
+            }
+        }
+    }
+
+    if unchanged_run > 0 {
+        println!("{}", format!("  ... {} unchanged line(s) ...", unchanged_run).dimmed());
+    }
+}
+
+/// Re-fetch a URL on a fixed interval and print a colored diff of what
+/// changed instead of dumping the whole document again. Debounces on
+/// matching ETag/bytes so identical fetches just print "no change".
+pub async fn watch_page_source(url: &str, interval_secs: u64) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", "Aluminum Web Browser - Watch Page Source".bold().green());
+    println!("Watching {} every {}s (Ctrl-C to stop)\n", url, interval_secs);
+
+    let mut previous: Option<PageSource> = None;
+
+    loop {
+        // Force revalidation on every poll after the first - otherwise a
+        // `Cache-Control: max-age` on the initial response would serve the
+        // same cached body for the whole freshness window, defeating the
+        // point of watching on a timer
+        let current = fetch_page_source(url, previous.is_some()).await?;
+
+        let cache_note = if current.from_cache { " (from cache)" } else { "" };
+        match &previous {
+            Some(prev) if prev.etag() == current.etag() && prev.content == current.content => {
+                println!("[{}] no change{}", current.url, cache_note);
+            }
+            Some(prev) => {
+                println!("[{}] changed{}:", current.url, cache_note);
+                print_diff(&pretty_print_html(&prev.content), &pretty_print_html(&current.content));
+            }
+            None => {
+                println!("[{}] initial fetch ({} bytes){}", current.url, current.content.len(), cache_note);
+            }
+        }
+
+        previous = Some(current);
+        sleep(Duration::from_secs(interval_secs)).await;
+    }
 }
 
 /// Function to save content to a file
-fn save_to_file(content: &str, filename: &str) -> io::Result<()> {
+pub fn save_to_file(content: &str, filename: &str) -> io::Result<()> {
     let path = Path::new(filename);
     let mut file = File::create(&path)?;
     file.write_all(content.as_bytes())?;
@@ -113,7 +369,7 @@ pub async fn view_page_source() -> Result<(), Box<dyn std::error::Error>> {
     let start_time = Instant::now();
 
     // Fetch page source
-    let page_source = fetch_page_source(url).await?;
+    let page_source = fetch_page_source(url, false).await?;
 
     // Stop spinner
     spinner.finish_with_message("Page source fetched successfully!");
@@ -123,6 +379,9 @@ pub async fn view_page_source() -> Result<(), Box<dyn std::error::Error>> {
     println!("URL: {}", page_source.url);
     println!("Status Code: {}", page_source.status_code);
     println!("Content Length: {} bytes", page_source.content.len());
+    if page_source.from_cache {
+        println!("Served from cache");
+    }
 
     // Display headers
     println!("\n{}", "Headers:".bold().cyan());
@@ -139,13 +398,15 @@ pub async fn view_page_source() -> Result<(), Box<dyn std::error::Error>> {
     println!("2. View pretty printed source");
     println!("3. Save raw source to file");
     println!("4. Save pretty printed source to file");
-    println!("5. Exit");
+    println!("5. View cache stats");
+    println!("6. Clear cache");
+    println!("7. Exit");
 
     loop {
-        println!("\nEnter your choice (1-5):");
+        println!("\nEnter your choice (1-7):");
         let mut choice = String::new();
         io::stdin().read_line(&mut choice)?;
-        
+
         match choice.trim() {
             "1" => println!("{}", page_source.content),
             "2" => println!("{}", pretty_html),
@@ -157,8 +418,13 @@ pub async fn view_page_source() -> Result<(), Box<dyn std::error::Error>> {
                 save_to_file(&pretty_html, "pretty_source.html")?;
                 println!("Pretty printed source saved to 'pretty_source.html'");
             },
-            "5" => break,
-            _ => println!("Invalid choice. Please enter a number between 1 and 5."),
+            "5" => println!("{}", cache_stats()),
+            "6" => {
+                clear_cache();
+                println!("Cache cleared");
+            },
+            "7" => break,
+            _ => println!("Invalid choice. Please enter a number between 1 and 7."),
         }
     }
 
@@ -176,3 +442,10 @@ pub fn run_view_page_source() {
         Err(e) => eprintln!("An error occurred: {}", e),
     }
 }
+
+// Error handling wrapper for watch mode
+pub async fn run_watch_page_source(url: &str, interval_secs: u64) {
+    if let Err(e) = watch_page_source(url, interval_secs).await {
+        eprintln!("An error occurred while watching the page: {}", e);
+    }
+}